@@ -5,7 +5,7 @@ use tao::{
   event::{DeviceEvent, ElementState, Event, KeyEvent, WindowEvent},
   event_loop::{ControlFlow, EventLoop},
   keyboard::{Key, ModifiersState},
-  window::WindowBuilder,
+  window::{CursorGrabMode, Window, WindowBuilder},
 };
 
 #[allow(clippy::single_match)]
@@ -40,7 +40,8 @@ fn main() {
           match key {
             Key::Escape => *control_flow = ControlFlow::Exit,
             Key::Character(ch) => match ch.to_lowercase().as_str() {
-              "g" => window.set_cursor_grab(!modifiers.shift_key()).unwrap(),
+              "g" => grab(&window, modifiers, CursorGrabMode::Confined),
+              "l" => grab(&window, modifiers, CursorGrabMode::Locked),
               "h" => window.set_cursor_visible(modifiers.shift_key()),
               _ => (),
             },
@@ -63,3 +64,14 @@ fn main() {
     }
   });
 }
+
+// Shift releases the cursor instead
+fn grab(window: &Window, modifiers: ModifiersState, mode: CursorGrabMode) {
+  let mode = match modifiers.shift_key() {
+    true => CursorGrabMode::None,
+    false => mode,
+  };
+  if let Err(error) = window.set_cursor_grab(mode) {
+    println!("failed to grab the cursor: {}", error);
+  }
+}
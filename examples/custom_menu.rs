@@ -97,6 +97,10 @@ fn main() {
         test_menu_item.set_selected(true);
         #[cfg(target_os = "macos")]
         test_menu_item.set_native_image(NativeImage::StatusUnavailable);
+        // change the icon at runtime
+        if let Err(error) = test_menu_item.set_icon(include_bytes!("icon_blue.ico").to_vec()) {
+          println!("Unable to set the icon: {}", error);
+        }
       }
       Event::MenuEvent {
         menu_id,
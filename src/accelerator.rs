@@ -4,6 +4,7 @@ use crate::keyboard::{KeyCode, ModifiersState, NativeKeyCode};
 use std::{
   borrow::Borrow,
   collections::hash_map::DefaultHasher,
+  fmt,
   hash::{Hash, Hasher},
   str::FromStr,
 };
@@ -65,6 +66,147 @@ impl FromStr for Accelerator {
   }
 }
 
+/// Formats the accelerator the way the platform displays it in menus, e.g. `Ctrl+Shift+A`.
+impl fmt::Display for Accelerator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.mods.control_key() {
+      f.write_str("Ctrl+")?;
+    }
+    if self.mods.shift_key() {
+      f.write_str("Shift+")?;
+    }
+    if self.mods.alt_key() {
+      f.write_str("Alt+")?;
+    }
+    if self.mods.super_key() {
+      #[cfg(target_os = "windows")]
+      f.write_str("Windows+")?;
+      #[cfg(target_os = "macos")]
+      f.write_str("Cmd+")?;
+      #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+      f.write_str("Super+")?;
+    }
+    f.write_str(&key_label(&self.key))
+  }
+}
+
+// Label of the main key of an accelerator.
+fn key_label(key: &KeyCode) -> String {
+  let label = match key {
+    KeyCode::KeyA => "A",
+    KeyCode::KeyB => "B",
+    KeyCode::KeyC => "C",
+    KeyCode::KeyD => "D",
+    KeyCode::KeyE => "E",
+    KeyCode::KeyF => "F",
+    KeyCode::KeyG => "G",
+    KeyCode::KeyH => "H",
+    KeyCode::KeyI => "I",
+    KeyCode::KeyJ => "J",
+    KeyCode::KeyK => "K",
+    KeyCode::KeyL => "L",
+    KeyCode::KeyM => "M",
+    KeyCode::KeyN => "N",
+    KeyCode::KeyO => "O",
+    KeyCode::KeyP => "P",
+    KeyCode::KeyQ => "Q",
+    KeyCode::KeyR => "R",
+    KeyCode::KeyS => "S",
+    KeyCode::KeyT => "T",
+    KeyCode::KeyU => "U",
+    KeyCode::KeyV => "V",
+    KeyCode::KeyW => "W",
+    KeyCode::KeyX => "X",
+    KeyCode::KeyY => "Y",
+    KeyCode::KeyZ => "Z",
+    KeyCode::Digit0 => "0",
+    KeyCode::Digit1 => "1",
+    KeyCode::Digit2 => "2",
+    KeyCode::Digit3 => "3",
+    KeyCode::Digit4 => "4",
+    KeyCode::Digit5 => "5",
+    KeyCode::Digit6 => "6",
+    KeyCode::Digit7 => "7",
+    KeyCode::Digit8 => "8",
+    KeyCode::Digit9 => "9",
+    KeyCode::Numpad0 => "Num 0",
+    KeyCode::Numpad1 => "Num 1",
+    KeyCode::Numpad2 => "Num 2",
+    KeyCode::Numpad3 => "Num 3",
+    KeyCode::Numpad4 => "Num 4",
+    KeyCode::Numpad5 => "Num 5",
+    KeyCode::Numpad6 => "Num 6",
+    KeyCode::Numpad7 => "Num 7",
+    KeyCode::Numpad8 => "Num 8",
+    KeyCode::Numpad9 => "Num 9",
+    KeyCode::NumpadAdd => "Num +",
+    KeyCode::NumpadSubtract => "Num -",
+    KeyCode::NumpadMultiply => "Num *",
+    KeyCode::NumpadDivide => "Num /",
+    KeyCode::NumpadDecimal => "Num .",
+    KeyCode::F1 => "F1",
+    KeyCode::F2 => "F2",
+    KeyCode::F3 => "F3",
+    KeyCode::F4 => "F4",
+    KeyCode::F5 => "F5",
+    KeyCode::F6 => "F6",
+    KeyCode::F7 => "F7",
+    KeyCode::F8 => "F8",
+    KeyCode::F9 => "F9",
+    KeyCode::F10 => "F10",
+    KeyCode::F11 => "F11",
+    KeyCode::F12 => "F12",
+    KeyCode::F13 => "F13",
+    KeyCode::F14 => "F14",
+    KeyCode::F15 => "F15",
+    KeyCode::F16 => "F16",
+    KeyCode::F17 => "F17",
+    KeyCode::F18 => "F18",
+    KeyCode::F19 => "F19",
+    KeyCode::F20 => "F20",
+    KeyCode::F21 => "F21",
+    KeyCode::F22 => "F22",
+    KeyCode::F23 => "F23",
+    KeyCode::F24 => "F24",
+    KeyCode::Comma => ",",
+    KeyCode::Minus => "-",
+    KeyCode::Period => ".",
+    KeyCode::Space => "Space",
+    KeyCode::Equal => "=",
+    KeyCode::Semicolon => ";",
+    KeyCode::Slash => "/",
+    KeyCode::Backslash => "\\",
+    KeyCode::Quote => "'",
+    KeyCode::Backquote => "`",
+    KeyCode::BracketLeft => "[",
+    KeyCode::BracketRight => "]",
+    KeyCode::Tab => "Tab",
+    KeyCode::Enter => "Enter",
+    KeyCode::Backspace => "Backspace",
+    KeyCode::Escape => "Esc",
+    KeyCode::Delete => "Del",
+    KeyCode::Insert => "Ins",
+    KeyCode::Home => "Home",
+    KeyCode::End => "End",
+    KeyCode::PageUp => "PgUp",
+    KeyCode::PageDown => "PgDn",
+    // These names match LibreOffice.
+    KeyCode::ArrowLeft => "Left",
+    KeyCode::ArrowRight => "Right",
+    KeyCode::ArrowUp => "Up",
+    KeyCode::ArrowDown => "Down",
+    KeyCode::MediaPlayPause => "Play/Pause",
+    KeyCode::MediaStop => "Stop",
+    KeyCode::MediaTrackNext => "Next Track",
+    KeyCode::MediaTrackPrevious => "Previous Track",
+    KeyCode::AudioVolumeUp => "Volume Up",
+    KeyCode::AudioVolumeDown => "Volume Down",
+    KeyCode::AudioVolumeMute => "Mute",
+    _ => return format!("{:?}", key),
+  };
+  label.to_string()
+}
+
 /// Represents the platform-agnostic keyboard modifiers, for command handling.
 ///
 /// **This does one thing: it allows specifying accelerators that use the Command key
@@ -266,8 +408,8 @@ fn parse_accelerator(accelerator_string: &str) -> Result<Accelerator, Accelerato
   let mut mods = ModifiersState::empty();
   let mut key = KeyCode::Unidentified(NativeKeyCode::Unidentified);
 
-  for raw in accelerator_string.to_uppercase().split('+') {
-    let token = raw.trim().to_string();
+  for raw in accelerator_string.split('+') {
+    let token = raw.trim().to_uppercase();
     if token.is_empty() {
       return Err(AcceleratorParseError(
         "Unexpected empty token while parsing accelerator".into(),
@@ -293,7 +435,7 @@ fn parse_accelerator(accelerator_string: &str) -> Result<Accelerator, Accelerato
       "CONTROL" | "CTRL" => {
         mods.set(ModifiersState::CONTROL, true);
       }
-      "COMMAND" | "CMD" | "SUPER" => {
+      "COMMAND" | "CMD" | "SUPER" | "META" => {
         mods.set(ModifiersState::SUPER, true);
       }
       "SHIFT" => {
@@ -305,22 +447,27 @@ fn parse_accelerator(accelerator_string: &str) -> Result<Accelerator, Accelerato
         #[cfg(not(target_os = "macos"))]
         mods.set(ModifiersState::CONTROL, true);
       }
-      _ => {
-        if let Ok(keycode) = KeyCode::from_str(token.to_uppercase().as_str()) {
-          match keycode {
-            KeyCode::Unidentified(_) => {
-              return Err(AcceleratorParseError(format!(
-                "Couldn't identify \"{}\" as a valid `KeyCode`",
-                token
-              )))
-            }
-            _ => key = keycode,
-          }
+      _ => match KeyCode::from_str(token.as_str()) {
+        Ok(KeyCode::Unidentified(_)) | Err(_) => {
+          return Err(AcceleratorParseError(format!(
+            "Couldn't identify \"{}\" as a valid modifier or `KeyCode` in \"{}\"",
+            raw.trim(),
+            accelerator_string
+          )))
         }
-      }
+        Ok(keycode) => key = keycode,
+      },
     }
   }
 
+  // modifiers alone can't trigger an accelerator
+  if key == KeyCode::Unidentified(NativeKeyCode::Unidentified) {
+    return Err(AcceleratorParseError(format!(
+      "Missing the main key of accelerator \"{}\"",
+      accelerator_string
+    )));
+  }
+
   Ok(Accelerator {
     // use the accelerator string as id
     id: Some(AcceleratorId(hash_string_to_u16(accelerator_string))),
@@ -435,4 +582,69 @@ fn test_parse_accelerator() {
 
   let acc = parse_accelerator("CTRL+");
   assert!(acc.is_err());
+
+  assert_eq!(
+    parse_accelerator("Alt+F4").unwrap(),
+    Accelerator {
+      id: Some(AcceleratorId::new("Alt+F4")),
+      mods: ModifiersState::ALT,
+      key: KeyCode::F4,
+    }
+  );
+  assert_eq!(
+    parse_accelerator("Super+Space").unwrap(),
+    Accelerator {
+      id: Some(AcceleratorId::new("Super+Space")),
+      mods: ModifiersState::SUPER,
+      key: KeyCode::Space,
+    }
+  );
+
+  // modifiers without a main key
+  let acc = parse_accelerator("Ctrl+Shift");
+  assert!(acc.is_err());
+
+  let acc = parse_accelerator("Ctrl+Foo+K");
+  assert!(acc.unwrap_err().to_string().contains("\"Foo\""));
+}
+
+#[test]
+fn test_display_accelerator() {
+  let display = |mods: ModifiersState, key: KeyCode| Accelerator::new(mods, key).to_string();
+
+  assert_eq!(
+    display(
+      ModifiersState::CONTROL | ModifiersState::SHIFT,
+      KeyCode::KeyA
+    ),
+    "Ctrl+Shift+A"
+  );
+  assert_eq!(display(ModifiersState::empty(), KeyCode::Numpad0), "Num 0");
+  assert_eq!(
+    display(ModifiersState::CONTROL, KeyCode::Numpad7),
+    "Ctrl+Num 7"
+  );
+  assert_eq!(
+    display(ModifiersState::empty(), KeyCode::NumpadAdd),
+    "Num +"
+  );
+  assert_eq!(
+    display(ModifiersState::ALT, KeyCode::NumpadDivide),
+    "Alt+Num /"
+  );
+  assert_eq!(display(ModifiersState::ALT, KeyCode::F4), "Alt+F4");
+  assert_eq!(display(ModifiersState::SHIFT, KeyCode::F13), "Shift+F13");
+  assert_eq!(display(ModifiersState::empty(), KeyCode::F24), "F24");
+  assert_eq!(
+    display(ModifiersState::CONTROL, KeyCode::Space),
+    "Ctrl+Space"
+  );
+  assert_eq!(
+    display(ModifiersState::empty(), KeyCode::MediaPlayPause),
+    "Play/Pause"
+  );
+  assert_eq!(
+    display(ModifiersState::SHIFT, KeyCode::AudioVolumeUp),
+    "Shift+Volume Up"
+  );
 }
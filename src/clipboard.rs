@@ -43,10 +43,47 @@ impl Clipboard {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** Returns `None` if another process keeps the clipboard open for too long.
   /// - **Android / iOS:** Unsupported
   pub fn read_text(&self) -> Option<String> {
     self.0.read_text()
   }
+
+  /// Writes an image into the clipboard, from 32bpp RGBA data in rows from top to bottom.
+  ///
+  /// Nothing is written if the length of `rgba` isn't `width * height * 4`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Written as a `CF_DIBV5` bitmap with straight alpha, along with a `CF_DIB`
+  ///   bitmap with premultiplied alpha for applications ignoring the alpha channel.
+  /// - **Android / iOS / macOS:** Unsupported
+  pub fn write_image(&mut self, rgba: &[u8], width: u32, height: u32) {
+    if rgba.len() != width as usize * height as usize * 4 {
+      return;
+    }
+    self.0.write_image(rgba, width, height);
+  }
+
+  /// The image in the clipboard, if any.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS / macOS:** Unsupported
+  pub fn read_image(&self) -> Option<ClipboardImage> {
+    self.0.read_image()
+  }
+}
+
+/// An image read from the clipboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+  /// The width of the image in pixels.
+  pub width: u32,
+  /// The height of the image in pixels.
+  pub height: u32,
+  /// The 32bpp RGBA data of the image, in rows from top to bottom.
+  pub rgba: Vec<u8>,
 }
 
 /// Identifier of a clipboard format.
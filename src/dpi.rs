@@ -76,7 +76,9 @@
 //!   displays. When this is available, the user may pick a per-monitor scaling factor from a set
 //!   of pre-defined settings. All "retina displays" have a scaling factor above 1.0 by default but
 //!   the specific value varies across devices.
-//! - **Linux:** TODO
+//! - **Linux:** GTK only supports integer scale factors, set per monitor by the desktop
+//!   environment or with the `GDK_SCALE` environment variable. Fractional scaling is done by
+//!   the compositor, so tao sees the rounded up scale factor.
 //! - **iOS:** Scale factors are set by Apple to the value that best suits the device, and range
 //!   from `1.0` to `3.0`. See [this article][apple_1] and [this article][apple_2] for more
 //!   information.
@@ -433,6 +435,23 @@ impl Size {
       Size::Logical(size) => size.to_physical(scale_factor),
     }
   }
+
+  /// Clamps `input` between `min` and `max`, comparing them in physical pixels. `min` wins when
+  /// it's larger than `max`.
+  pub fn clamp<S: Into<Size>>(input: S, min: S, max: S, scale_factor: f64) -> Size {
+    let (input, min, max) = (
+      input.into().to_physical::<f64>(scale_factor),
+      min.into().to_physical::<f64>(scale_factor),
+      max.into().to_physical::<f64>(scale_factor),
+    );
+
+    let clamp = |input: f64, min: f64, max: f64| input.min(max).max(min);
+
+    let width = clamp(input.width, min.width, max.width);
+    let height = clamp(input.height, min.height, max.height);
+
+    PhysicalSize::new(width, height).into()
+  }
 }
 
 impl<P: Pixel> From<PhysicalSize<P>> for Size {
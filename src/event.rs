@@ -43,7 +43,7 @@ use crate::{
   accelerator::AcceleratorId,
   dpi::{PhysicalPosition, PhysicalSize},
   keyboard::{self, ModifiersState},
-  menu::{MenuId, MenuType},
+  menu::{HighlightedMenuItem, MenuId, MenuType},
   platform_impl,
   window::{Theme, WindowId},
 };
@@ -86,6 +86,43 @@ pub enum Event<'a, T: 'static> {
     window_id: Option<WindowId>,
     menu_id: MenuId,
     origin: MenuType,
+    /// The modifiers held when the item was activated, e.g. to run a variant of the command on
+    /// <kbd>Shift</kbd>-click.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **macOS / Linux:** Always empty.
+    modifiers: ModifiersState,
+  },
+
+  /// Emitted right before a menu opens, with the id given to
+  /// [`MenuExtWindows::set_will_open_event`](crate::platform::windows::MenuExtWindows::set_will_open_event),
+  /// so its items can be updated.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux / iOS / Android:** Unsupported.
+  #[non_exhaustive]
+  MenuWillOpen {
+    window_id: Option<WindowId>,
+    menu_id: MenuId,
+  },
+
+  /// Emitted when the highlighted item of a menu changes, with the mouse or the keyboard.
+  ///
+  /// `item` is `None` when no item is highlighted anymore, e.g. when the menu is closed.
+  /// `help_text` is the hint of the item set with
+  /// [`CustomMenuItemExtWindows::set_help_text`](crate::platform::windows::CustomMenuItemExtWindows::set_help_text),
+  /// if any.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux / iOS / Android:** Unsupported.
+  #[non_exhaustive]
+  MenuItemHighlighted {
+    window_id: Option<WindowId>,
+    item: Option<HighlightedMenuItem>,
+    help_text: Option<String>,
   },
 
   /// Emitted when tray has been clicked.
@@ -107,6 +144,20 @@ pub enum Event<'a, T: 'static> {
   /// - **iOS / Android:** Unsupported.
   GlobalShortcutEvent(AcceleratorId),
 
+  /// Emitted when a monitor is connected or disconnected, or when the resolution, position or
+  /// scale factor of a monitor changes.
+  ///
+  /// [`available_monitors`](crate::event_loop::EventLoopWindowTarget::available_monitors) already
+  /// reflects the new configuration when this is emitted. Tao doesn't move the windows left
+  /// outside of the remaining monitors, this is up to the application.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Consecutive changes, like the ones of a monitor being plugged in, are
+  ///   gathered into a single event.
+  /// - **macOS / Linux / iOS / Android:** Unsupported.
+  DisplaysChanged,
+
   /// Emitted when the application has been suspended.
   Suspended,
 
@@ -188,10 +239,25 @@ impl<T: Clone> Clone for Event<'static, T> {
         window_id,
         menu_id,
         origin,
+        modifiers,
       } => MenuEvent {
         window_id: *window_id,
         menu_id: *menu_id,
         origin: *origin,
+        modifiers: *modifiers,
+      },
+      MenuWillOpen { window_id, menu_id } => MenuWillOpen {
+        window_id: *window_id,
+        menu_id: *menu_id,
+      },
+      MenuItemHighlighted {
+        window_id,
+        item,
+        help_text,
+      } => MenuItemHighlighted {
+        window_id: *window_id,
+        item: item.clone(),
+        help_text: help_text.clone(),
       },
       TrayEvent {
         bounds,
@@ -203,6 +269,7 @@ impl<T: Clone> Clone for Event<'static, T> {
         position: *position,
       },
       GlobalShortcutEvent(accelerator_id) => GlobalShortcutEvent(*accelerator_id),
+      DisplaysChanged => DisplaysChanged,
     }
   }
 }
@@ -225,10 +292,22 @@ impl<'a, T> Event<'a, T> {
         window_id,
         menu_id,
         origin,
+        modifiers,
       } => Ok(MenuEvent {
         window_id,
         menu_id,
         origin,
+        modifiers,
+      }),
+      MenuWillOpen { window_id, menu_id } => Ok(MenuWillOpen { window_id, menu_id }),
+      MenuItemHighlighted {
+        window_id,
+        item,
+        help_text,
+      } => Ok(MenuItemHighlighted {
+        window_id,
+        item,
+        help_text,
       }),
       TrayEvent {
         bounds,
@@ -240,6 +319,7 @@ impl<'a, T> Event<'a, T> {
         position,
       }),
       GlobalShortcutEvent(accelerator_id) => Ok(GlobalShortcutEvent(accelerator_id)),
+      DisplaysChanged => Ok(DisplaysChanged),
     }
   }
 
@@ -264,10 +344,22 @@ impl<'a, T> Event<'a, T> {
         window_id,
         menu_id,
         origin,
+        modifiers,
       } => Some(MenuEvent {
         window_id,
         menu_id,
         origin,
+        modifiers,
+      }),
+      MenuWillOpen { window_id, menu_id } => Some(MenuWillOpen { window_id, menu_id }),
+      MenuItemHighlighted {
+        window_id,
+        item,
+        help_text,
+      } => Some(MenuItemHighlighted {
+        window_id,
+        item,
+        help_text,
       }),
       TrayEvent {
         bounds,
@@ -279,6 +371,7 @@ impl<'a, T> Event<'a, T> {
         position,
       }),
       GlobalShortcutEvent(accelerator_id) => Some(GlobalShortcutEvent(accelerator_id)),
+      DisplaysChanged => Some(DisplaysChanged),
     }
   }
 }
@@ -347,8 +440,20 @@ pub enum WindowEvent<'a> {
   HoveredFileCancelled,
 
   /// The window received a unicode character.
+  ///
+  /// This is the text typed with the current keyboard layout, e.g. a single `ê` after the dead
+  /// key `^` and `e`. Unlike [`KeyEvent::text`], it includes the text of the input method.
   ReceivedImeText(String),
 
+  /// The input method composed text for the window, e.g. Japanese or Chinese text.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Only the compose sequences of the GTK input method are reported, without
+  ///   `Commit`. The text is only delivered by [`WindowEvent::ReceivedImeText`].
+  /// - **iOS / Android:** Unsupported.
+  Ime(Ime),
+
   /// The window gained or lost focus.
   ///
   /// The parameter is true if the window has gained focus, and false if it has lost focus.
@@ -450,6 +555,13 @@ pub enum WindowEvent<'a> {
   /// by the OS, but it can be changed to any value.
   ///
   /// For more information about DPI in general, see the [`dpi`](crate::dpi) module.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The suggested size keeps the logical inner size of the window, unless it is
+  ///   maximized or fullscreen. While the window is dragged across monitors, it is moved so the cursor
+  ///   stays at the same place of the title bar. The icons of the menu items are reloaded at the new
+  ///   scale factor once the event is handled.
   ScaleFactorChanged {
     scale_factor: f64,
     new_inner_size: &'a mut PhysicalSize<u32>,
@@ -476,6 +588,7 @@ impl Clone for WindowEvent<'static> {
       HoveredFile(file) => HoveredFile(file.clone()),
       HoveredFileCancelled => HoveredFileCancelled,
       ReceivedImeText(c) => ReceivedImeText(c.clone()),
+      Ime(ime) => Ime(ime.clone()),
       Focused(f) => Focused(*f),
       KeyboardInput {
         device_id,
@@ -567,6 +680,7 @@ impl<'a> WindowEvent<'a> {
       HoveredFile(file) => Some(HoveredFile(file)),
       HoveredFileCancelled => Some(HoveredFileCancelled),
       ReceivedImeText(c) => Some(ReceivedImeText(c)),
+      Ime(ime) => Some(Ime(ime)),
       Focused(focused) => Some(Focused(focused)),
       KeyboardInput {
         device_id,
@@ -676,6 +790,12 @@ pub enum DeviceEvent {
   /// Change in physical position of a pointing device.
   ///
   /// This represents raw, unfiltered physical motion. Not to be confused with `WindowEvent::CursorMoved`.
+  /// It keeps being sent when the cursor is stuck at an edge of the screen or locked.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Devices reporting absolute positions, like pen tablets or remote desktop
+  ///   sessions, send the difference between positions in pixels.
   #[non_exhaustive]
   MouseMotion {
     /// (x, y) change in position in unspecified units.
@@ -820,6 +940,29 @@ impl KeyEvent {
   }
 }
 
+/// Describes a change of the composition of the input method, see [`WindowEvent::Ime`].
+///
+/// A composition starts with `Enabled`, shows its text with `Preedit` while it changes and ends
+/// with `Disabled`, after a `Commit` if the text was accepted. The committed text is also
+/// delivered by [`WindowEvent::ReceivedImeText`], so an application only drawing the preedit text
+/// itself can keep handling text input there.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Ime {
+  /// A composition started.
+  Enabled,
+  /// The text being composed changed, empty when it was cleared.
+  ///
+  /// The range is the byte offsets of the cursor, or of the selected part of the text, in it.
+  /// `None` hides the cursor.
+  Preedit(String, Option<(usize, usize)>),
+  /// The composition was accepted with this text.
+  Commit(String),
+  /// The composition ended.
+  Disabled,
+}
+
 /// Describes touch-screen input state.
 #[non_exhaustive]
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
@@ -859,6 +1002,14 @@ pub enum TrayEvent {
   /// - **macOS / Linux:** Unsupported
   ///
   DoubleClick,
+  /// Fired when the notification shown by the tray icon is clicked, see
+  /// [`SystemTrayExtWindows::show_notification`](crate::platform::windows::SystemTrayExtWindows::show_notification).
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux:** Unsupported
+  ///
+  NotificationClick,
 }
 
 /// Describes a rectangle including position (x - y axis) and size.
@@ -51,6 +51,9 @@ impl ShortcutManager {
   }
 
   /// Register a global shortcut of `Accelerator` who trigger `GlobalShortcutEvent` in the event loop.
+  ///
+  /// Returns [`ShortcutManagerError::AcceleratorAlreadyRegistered`] if the shortcut is already
+  /// registered by this manager, or on Windows by another application.
   pub fn register(
     &mut self,
     accelerator: Accelerator,
@@ -60,8 +63,11 @@ impl ShortcutManager {
         accelerator,
       ));
     }
-    self.registered_hotkeys.push(accelerator.clone());
-    self.p.register(accelerator)
+    // only track the accelerator once the platform accepted it, so it can be registered again
+    // after a failure
+    let global_shortcut = self.p.register(accelerator.clone())?;
+    self.registered_hotkeys.push(accelerator);
+    Ok(global_shortcut)
   }
 
   /// Unregister all `Accelerator` registered by the manager instance.
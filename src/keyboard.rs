@@ -769,6 +769,7 @@ impl FromStr for KeyCode {
       "MEDIANEXTTRACK" => KeyCode::MediaTrackNext,
       "MEDIAPREVIOUSTRACK" => KeyCode::MediaTrackPrevious,
       "MEDIAPLAYPAUSE" => KeyCode::MediaPlayPause,
+      "MEDIASTOP" => KeyCode::MediaStop,
       "LAUNCHMAIL" => KeyCode::LaunchMail,
 
       "SUSPEND" => KeyCode::Suspend,
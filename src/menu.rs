@@ -19,12 +19,15 @@
 //! [context_menu]: crate::menu::ContextMenu
 
 use std::{
-  collections::hash_map::DefaultHasher,
+  collections::{hash_map::DefaultHasher, HashMap},
+  error, fmt,
   hash::{Hash, Hasher},
+  marker::PhantomData,
 };
 
 use crate::{
   accelerator::Accelerator,
+  error::OsError,
   platform_impl::{Menu as MenuPlatform, MenuItemAttributes as CustomMenuItemPlatform},
 };
 
@@ -43,11 +46,11 @@ pub struct MenuBar(pub(crate) Menu);
 
 /// A custom menu item.
 pub struct MenuItemAttributes<'a> {
-  id: MenuId,
-  title: &'a str,
-  keyboard_accelerator: Option<Accelerator>,
-  enabled: bool,
-  selected: bool,
+  pub(crate) id: MenuId,
+  pub(crate) title: &'a str,
+  pub(crate) keyboard_accelerator: Option<Accelerator>,
+  pub(crate) enabled: bool,
+  pub(crate) selected: bool,
 }
 
 impl<'a> MenuItemAttributes<'a> {
@@ -187,6 +190,227 @@ impl Default for MenuBar {
   }
 }
 
+/// Builds a `MenuBar` or a `ContextMenu` by chaining its items.
+///
+/// The attributes of a custom item are set right after adding it with `item`. The items of the
+/// menu and its submenus are returned along with the menu, to update them later.
+///
+/// ```no_run
+/// use tao::{
+///   accelerator::{Accelerator, SysMods},
+///   event_loop::EventLoop,
+///   keyboard::KeyCode,
+///   menu::{MenuBar, MenuBuilder, MenuId, MenuItem},
+///   window::WindowBuilder,
+/// };
+///
+/// let event_loop = EventLoop::new();
+/// let (menu, mut items) = MenuBuilder::<MenuBar>::new()
+///   .submenu("File", true, |file| {
+///     file
+///       .item(MenuId(1), "Open")
+///       .accelerator(Accelerator::new(SysMods::Cmd, KeyCode::KeyO))
+///       .item(MenuId(2), "Save")
+///       .enabled(false)
+///       .native_item(MenuItem::Quit)
+///   })
+///   .build();
+/// let _window = WindowBuilder::new()
+///   .with_menu(menu)
+///   .build(&event_loop)
+///   .unwrap();
+/// items.get_mut(&MenuId(2)).unwrap().set_enabled(true);
+/// ```
+pub struct MenuBuilder<M> {
+  menu: Menu,
+  items: HashMap<MenuId, CustomMenuItem>,
+  _marker: PhantomData<M>,
+}
+
+/// A custom item added by [`MenuBuilder::item`], whose attributes can be set before adding the
+/// next item.
+pub struct MenuItemBuilder<M> {
+  builder: MenuBuilder<M>,
+  item: PendingItem,
+}
+
+struct PendingItem {
+  id: MenuId,
+  title: String,
+  accelerator: Option<Accelerator>,
+  enabled: bool,
+  selected: bool,
+}
+
+impl<M> MenuBuilder<M> {
+  fn with_menu(menu_platform: MenuPlatform, menu_type: MenuType) -> Self {
+    Self {
+      menu: Menu {
+        menu_platform,
+        menu_type,
+      },
+      items: HashMap::new(),
+      _marker: PhantomData,
+    }
+  }
+
+  /// Adds a custom item, enabled and not selected unless its attributes are set.
+  pub fn item(self, id: MenuId, title: &str) -> MenuItemBuilder<M> {
+    MenuItemBuilder {
+      builder: self,
+      item: PendingItem {
+        id,
+        title: title.to_string(),
+        accelerator: None,
+        enabled: true,
+        selected: false,
+      },
+    }
+  }
+
+  /// Adds a native item.
+  pub fn native_item(mut self, item: MenuItem) -> Self {
+    if let Some(item) = self
+      .menu
+      .menu_platform
+      .add_native_item(item, self.menu.menu_type)
+    {
+      self.items.insert(item.clone().id(), item);
+    }
+    self
+  }
+
+  /// Adds a submenu, whose items are added by `build`.
+  pub fn submenu<B: Into<Self>>(
+    mut self,
+    title: &str,
+    enabled: bool,
+    build: impl FnOnce(Self) -> B,
+  ) -> Self {
+    let menu_platform = match self.menu.menu_type {
+      MenuType::MenuBar => MenuPlatform::new(),
+      MenuType::ContextMenu => MenuPlatform::new_popup_menu(),
+    };
+    let submenu: Self = build(Self::with_menu(menu_platform, self.menu.menu_type)).into();
+    self
+      .menu
+      .menu_platform
+      .add_submenu(title, enabled, submenu.menu.menu_platform);
+    self.items.extend(submenu.items);
+    self
+  }
+
+  fn add_item(&mut self, item: PendingItem) {
+    let custom_item = self.menu.menu_platform.add_item(
+      item.id,
+      &item.title,
+      item.accelerator,
+      item.enabled,
+      item.selected,
+      self.menu.menu_type,
+    );
+    self.items.insert(item.id, custom_item);
+  }
+}
+
+impl<M> MenuItemBuilder<M> {
+  /// Assigns a keyboard shortcut to the item, see [`MenuItemAttributes::with_accelerators`].
+  pub fn accelerator(mut self, accelerator: Accelerator) -> Self {
+    self.item.accelerator = Some(accelerator);
+    self
+  }
+
+  /// Assigns the state of the item, see [`MenuItemAttributes::with_enabled`].
+  pub fn enabled(mut self, enabled: bool) -> Self {
+    self.item.enabled = enabled;
+    self
+  }
+
+  /// Assigns the checkbox style of the item, see [`MenuItemAttributes::with_selected`].
+  pub fn selected(mut self, selected: bool) -> Self {
+    self.item.selected = selected;
+    self
+  }
+
+  /// Adds another custom item, see [`MenuBuilder::item`].
+  pub fn item(self, id: MenuId, title: &str) -> MenuItemBuilder<M> {
+    MenuBuilder::from(self).item(id, title)
+  }
+
+  /// Adds a native item, see [`MenuBuilder::native_item`].
+  pub fn native_item(self, item: MenuItem) -> MenuBuilder<M> {
+    MenuBuilder::from(self).native_item(item)
+  }
+
+  /// Adds a submenu, see [`MenuBuilder::submenu`].
+  pub fn submenu<B: Into<MenuBuilder<M>>>(
+    self,
+    title: &str,
+    enabled: bool,
+    build: impl FnOnce(MenuBuilder<M>) -> B,
+  ) -> MenuBuilder<M> {
+    MenuBuilder::from(self).submenu(title, enabled, build)
+  }
+}
+
+impl<M> From<MenuItemBuilder<M>> for MenuBuilder<M> {
+  fn from(item_builder: MenuItemBuilder<M>) -> Self {
+    let mut builder = item_builder.builder;
+    builder.add_item(item_builder.item);
+    builder
+  }
+}
+
+impl MenuBuilder<MenuBar> {
+  /// Creates a builder for a `MenuBar`.
+  pub fn new() -> Self {
+    Self::with_menu(MenuPlatform::new(), MenuType::MenuBar)
+  }
+
+  /// Returns the menu and its custom items, including the items of its submenus.
+  pub fn build(self) -> (MenuBar, HashMap<MenuId, CustomMenuItem>) {
+    (MenuBar(self.menu), self.items)
+  }
+}
+
+impl MenuItemBuilder<MenuBar> {
+  /// Adds the item and returns the menu, see [`MenuBuilder::build`].
+  pub fn build(self) -> (MenuBar, HashMap<MenuId, CustomMenuItem>) {
+    MenuBuilder::from(self).build()
+  }
+}
+
+impl Default for MenuBuilder<MenuBar> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl MenuBuilder<ContextMenu> {
+  /// Creates a builder for a `ContextMenu`.
+  pub fn new() -> Self {
+    Self::with_menu(MenuPlatform::new_popup_menu(), MenuType::ContextMenu)
+  }
+
+  /// Returns the menu and its custom items, including the items of its submenus.
+  pub fn build(self) -> (ContextMenu, HashMap<MenuId, CustomMenuItem>) {
+    (ContextMenu(self.menu), self.items)
+  }
+}
+
+impl MenuItemBuilder<ContextMenu> {
+  /// Adds the item and returns the menu, see [`MenuBuilder::build`].
+  pub fn build(self) -> (ContextMenu, HashMap<MenuId, CustomMenuItem>) {
+    MenuBuilder::from(self).build()
+  }
+}
+
+impl Default for MenuBuilder<ContextMenu> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 /// A menu item, bound to a pre-defined native action.
 ///
 /// Note some platforms might not support some of the variants.
@@ -366,10 +590,38 @@ impl CustomMenuItem {
     self.0.set_selected(is_selected)
   }
 
-  // todo: Add set_icon
-  // pub fn set_icon(&mut self, icon: Vec<u8>) {
-  //   self.0.set_icon(icon)
-  // }
+  /// Returns whether the menu item is enabled.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS:** Unsupported, always `false`.
+  pub fn is_enabled(&self) -> bool {
+    self.0.is_enabled()
+  }
+
+  /// Returns whether the menu item is selected, e.g. after the user toggled it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Always `false` for items that aren't rendered as check items.
+  /// - **Android / iOS:** Unsupported, always `false`.
+  pub fn is_selected(&self) -> bool {
+    self.0.is_selected()
+  }
+
+  /// Modifies the icon of the menu item, from the content of an `.ico` file.
+  ///
+  /// Returns an error if the icon couldn't be loaded or applied.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The icon is rendered at the small icon size of the DPI of the window the menu
+  ///   is attached to, or of the system before it is attached, and reloaded when the DPI changes.
+  /// - **Linux / macOS / Android / iOS:** Unsupported, always returns an error and leaves the
+  ///   item unchanged.
+  pub fn set_icon(&mut self, icon: Vec<u8>) -> Result<(), OsError> {
+    self.0.set_icon(icon)
+  }
 }
 
 /// Identifier of a custom menu item.
@@ -410,6 +662,46 @@ pub enum MenuType {
   ContextMenu,
 }
 
+/// The item highlighted in a menu, see
+/// [`Event::MenuItemHighlighted`](crate::event::Event::MenuItemHighlighted).
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum HighlightedMenuItem {
+  /// A custom or native item.
+  Item(MenuId),
+  /// The header of a submenu, which has no id, with its position in the parent menu and its title.
+  Submenu { position: u32, title: String },
+}
+
+/// An error whose cause a menu to fail to be attached to a window.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum MenuError {
+  /// The menu couldn't be set on the window or handle its messages, e.g. because the window was
+  /// destroyed.
+  AttachFailed(OsError),
+  /// The menu bar couldn't be drawn. The menu is removed from the window.
+  DrawFailed(OsError),
+}
+
+impl error::Error for MenuError {}
+impl fmt::Display for MenuError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+    match self {
+      MenuError::AttachFailed(e) => f.pad(&format!("unable to attach the menu: {}", e)),
+      MenuError::DrawFailed(e) => f.pad(&format!("unable to draw the menu bar: {}", e)),
+    }
+  }
+}
+
+impl From<MenuError> for OsError {
+  fn from(error: MenuError) -> Self {
+    match error {
+      MenuError::AttachFailed(e) | MenuError::DrawFailed(e) => e,
+    }
+  }
+}
+
 fn hash_string_to_u16(title: &str) -> u16 {
   let mut s = DefaultHasher::new();
   title.to_uppercase().hash(&mut s);
@@ -86,6 +86,13 @@ impl VideoMode {
     self.video_mode.refresh_rate()
   }
 
+  /// Returns the refresh rate of this video mode in millihertz, to compare it with
+  /// [`MonitorHandle::refresh_rate_millihertz`]. It has the precision of [`VideoMode::refresh_rate`].
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> u32 {
+    self.refresh_rate() as u32 * 1000
+  }
+
   /// Returns the monitor that this video mode is valid for. Each monitor has
   /// a separate set of valid video modes.
   #[inline]
@@ -127,6 +134,8 @@ impl MonitorHandle {
   }
 
   /// Returns the monitor's resolution.
+  ///
+  /// Returns a zero size if the monitor doesn't exist anymore.
   #[inline]
   pub fn size(&self) -> PhysicalSize<u32> {
     self.inner.size()
@@ -139,6 +148,17 @@ impl MonitorHandle {
     self.inner.position()
   }
 
+  /// Returns the position and size of the part of the monitor available to windows, i.e. without
+  /// the taskbar, dock or panels, wherever they are docked.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Always the full bounds of the monitor.
+  #[inline]
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    self.inner.work_area()
+  }
+
   /// Returns the scale factor that can be used to map logical pixels to physical pixels, and vice versa.
   ///
   /// See the [`dpi`](crate::dpi) module for more information.
@@ -151,7 +171,22 @@ impl MonitorHandle {
     self.inner.scale_factor()
   }
 
-  /// Returns all fullscreen video modes supported by this monitor.
+  /// Returns the current refresh rate of the monitor in millihertz, e.g. `59940` for 59.94 Hz.
+  ///
+  /// Returns `None` if it is unknown, or if the monitor doesn't exist anymore.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android:** Unsupported.
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    self.inner.refresh_rate_millihertz()
+  }
+
+  /// Returns all fullscreen video modes supported by this monitor, to pass to
+  /// [`Fullscreen::Exclusive`](crate::window::Fullscreen::Exclusive).
+  ///
+  /// Modes only differing by internal details, like the scaling of the output, are listed once.
   ///
   /// ## Platform-specific
   /// - **Linux:** Unsupported. This will always return empty iterator.
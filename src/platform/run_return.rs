@@ -32,6 +32,9 @@ pub trait EventLoopExtRunReturn {
   ///
   /// - **Unix-alikes** (**X11** or **Wayland**): This function returns `1` upon disconnection from
   ///   the display server.
+  /// - **Windows:** Windows outlive this function and keep their state, so it can be called again
+  ///   later. It also returns when the thread receives `WM_QUIT`, with the exit code passed to
+  ///   `PostQuitMessage`.
   fn run_return<F>(&mut self, event_handler: F) -> i32
   where
     F: FnMut(Event<'_, Self::UserEvent>, &EventLoopWindowTarget<Self::UserEvent>, &mut ControlFlow);
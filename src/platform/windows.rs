@@ -5,20 +5,52 @@
 
 use std::path::Path;
 
-pub use crate::platform_impl::hit_test;
+pub use crate::platform_impl::{
+  hit_test, vk_to_key, AcceleratorText, BackdropMaterial, MenuEntry, MenuItemDrawContext,
+  ProgressState, ResizeDirection,
+};
+#[cfg(feature = "tray")]
+use crate::system_tray::SystemTray;
 use crate::{
-  dpi::PhysicalSize,
-  event::DeviceId,
-  event_loop::EventLoop,
+  accelerator::Accelerator,
+  dpi::{PhysicalPosition, PhysicalSize, Position},
+  error::{ExternalError, OsError},
+  event::{DeviceId, KeyEvent},
+  event_loop::{EventLoop, EventLoopWindowTarget},
+  menu::{ContextMenu, CustomMenuItem, MenuBar, MenuError, MenuId, MenuItem, MenuItemAttributes},
   monitor::MonitorHandle,
   platform_impl::{EventLoop as WindowsEventLoop, Parent, WinIcon},
   window::{BadIcon, Icon, Theme, Window, WindowBuilder},
 };
 use libc;
-use windows::Win32::{
-  Foundation::HWND,
-  UI::{Input::KeyboardAndMouse::*, WindowsAndMessaging::*},
-};
+use windows::Win32::{Foundation::HWND, UI::WindowsAndMessaging::*};
+
+/// Additional methods on `SystemTray` that are specific to Windows.
+#[cfg(feature = "tray")]
+pub trait SystemTrayExtWindows {
+  /// Shows a notification next to the tray icon, with `icon` or the icon of the tray if `None`.
+  ///
+  /// Clicking it sends a [`TrayEvent::NotificationClick`](crate::event::TrayEvent::NotificationClick).
+  /// The system truncates the title to 63 characters and the body to 255. The notification isn't
+  /// shown while Focus Assist is on or notifications are turned off, without any error.
+  fn show_notification(&self, title: &str, body: &str, icon: Option<Icon>);
+
+  /// Sets the tray icon from an [`Icon`], e.g. to update a status indicator from RGBA data.
+  fn set_tray_icon(&mut self, icon: Icon);
+}
+
+#[cfg(feature = "tray")]
+impl SystemTrayExtWindows for SystemTray {
+  #[inline]
+  fn show_notification(&self, title: &str, body: &str, icon: Option<Icon>) {
+    self.0.show_notification(title, body, icon)
+  }
+
+  #[inline]
+  fn set_tray_icon(&mut self, icon: Icon) {
+    self.0.set_tray_icon(icon)
+  }
+}
 
 /// Additional methods on `EventLoop` that are specific to Windows.
 pub trait EventLoopExtWindows {
@@ -73,6 +105,54 @@ impl<T> EventLoopExtWindows for EventLoop<T> {
   }
 }
 
+/// Additional methods on `EventLoopWindowTarget` that are specific to Windows.
+pub trait EventLoopWindowTargetExtWindows {
+  /// Handles the items of `menu` on `hwnd`, without making it the menu bar of the window.
+  ///
+  /// Use this for windows that can't have a menu bar, such as child windows and custom controls
+  /// drawing their own menu bar. A `WM_COMMAND` sent to `hwnd` with the id of an item, e.g. by
+  /// a button, and the item accelerators, while the focus is in `hwnd` or one of its children,
+  /// are reported as [`Event::MenuEvent`](crate::event::Event::MenuEvent) for the top-level
+  /// window containing `hwnd`.
+  ///
+  /// A child window built with [`WindowBuilder::with_menu`] is handled this way automatically.
+  fn attach_menu(&self, hwnd: *mut libc::c_void, menu: MenuBar) -> Result<(), MenuError>;
+}
+
+impl<T> EventLoopWindowTargetExtWindows for EventLoopWindowTarget<T> {
+  #[inline]
+  fn attach_menu(&self, hwnd: *mut libc::c_void, menu: MenuBar) -> Result<(), MenuError> {
+    self.p.attach_menu(HWND(hwnd as _), menu.0.menu_platform)
+  }
+}
+
+/// Additional methods on `KeyEvent` that are specific to Windows.
+pub trait KeyEventExtWindows {
+  /// Returns how many keystrokes this event stands for, as reported by the system.
+  ///
+  /// This is more than one when a held key auto-repeats faster than the events are handled. Always
+  /// `1` for a key release. Use [`KeyEvent::repeat`] to tell an initial press from a repeat.
+  fn repeat_count(&self) -> u16;
+
+  /// Returns the scancode of the key, with `0xE000` added for extended keys, as used by
+  /// [`KeyCode::to_scancode`](crate::keyboard::KeyCode::to_scancode).
+  ///
+  /// Unlike [`KeyEvent::physical_key`], it is available for the keys without a `KeyCode` too.
+  fn scancode(&self) -> u32;
+}
+
+impl KeyEventExtWindows for KeyEvent {
+  #[inline]
+  fn repeat_count(&self) -> u16 {
+    self.platform_specific.repeat_count
+  }
+
+  #[inline]
+  fn scancode(&self) -> u32 {
+    self.platform_specific.scancode
+  }
+}
+
 /// Additional methods on `Window` that are specific to Windows.
 pub trait WindowExtWindows {
   /// Returns the HINSTANCE of the window
@@ -88,7 +168,8 @@ pub trait WindowExtWindows {
   /// If an application has create a modal dialog box by disabling its owner window
   /// (as described in [`WindowBuilderExtWindows::with_owner_window`]), the application must enable
   /// the owner window before destroying the dialog box.
-  /// Otherwise, another window will receive the keyboard focus and be activated.
+  /// Otherwise, another window will receive the keyboard focus and be activated. Enabling it
+  /// while no window has the focus anymore activates it again.
   ///
   /// If a child window is disabled, it is ignored when the system tries to determine which
   /// window should receive mouse messages.
@@ -103,6 +184,12 @@ pub trait WindowExtWindows {
   /// Returns the current window theme.
   fn theme(&self) -> Theme;
 
+  /// Sets the theme of the window and its title bar, or follows the system theme if `None`.
+  ///
+  /// While following the system, [`WindowEvent::ThemeChanged`](crate::event::WindowEvent::ThemeChanged)
+  /// is sent whenever the system theme changes. Dark title bars require Windows 10 1809 or newer.
+  fn set_theme(&self, theme: Option<Theme>);
+
   /// Reset the dead key state of the keyboard.
   ///
   /// This is useful when a dead key is bound to trigger an action. Then
@@ -113,8 +200,74 @@ pub trait WindowExtWindows {
   /// Starts the resizing drag from given edge
   fn begin_resize_drag(&self, edge: isize, button: u32, x: i32, y: i32);
 
+  /// Resizes the window from `direction` with the left mouse button until the button is
+  /// released, e.g. from the edges of a window without decorations.
+  ///
+  /// The size bounds of the window are respected. Returns an [`ExternalError::NotSupported`] if
+  /// the left mouse button isn't pressed.
+  fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError>;
+
   /// Whether to show the window icon in the taskbar or not.
+  ///
+  /// The button stays hidden after hiding and showing the window again.
   fn set_skip_taskbar(&self, skip: bool);
+
+  /// Returns the position of the cursor relative to the client area of the window, or `None` if
+  /// the cursor is outside of it.
+  ///
+  /// Unlike [`WindowEvent::CursorMoved`](crate::event::WindowEvent::CursorMoved), the position
+  /// is read when called, e.g. to show a popup at the cursor from a keyboard shortcut.
+  fn cursor_position(&self) -> Option<PhysicalPosition<f64>>;
+
+  /// Keeps the `width:height` ratio of the inner size while the user resizes the window, or
+  /// removes the constraint if `None`.
+  ///
+  /// The edge or corner being dragged determines which side follows the other. The minimum and
+  /// maximum inner sizes are still respected, unless no size within them has this ratio. The
+  /// current size of the window is left unchanged.
+  fn set_resize_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>);
+
+  /// Shows a progress bar over the taskbar button of the window, e.g. during a download.
+  ///
+  /// `progress` ranges from `0.0` to `1.0` and keeps its previous value when `None`. It is
+  /// ignored by the `None` and `Indeterminate` states.
+  fn set_progress_bar(&self, state: ProgressState, progress: Option<f64>);
+
+  /// Shows a small `icon` over the corner of the taskbar button of the window, e.g. a badge for
+  /// unread messages, or removes it if `None`.
+  ///
+  /// `description` is read by screen readers. The overlay is independent from the taskbar icon,
+  /// and is only shown while the taskbar uses large buttons. A 16x16 icon is recommended.
+  fn set_overlay_icon(&self, icon: Option<Icon>, description: Option<&str>);
+
+  /// Draws `material` behind the window, e.g. `Mica` for the main window of an app.
+  ///
+  /// The material only shows through the parts of the window drawn transparent. All of them are
+  /// supported from Windows 11 build 22621, while earlier Windows 11 builds only support `Mica`.
+  /// Anything else is ignored with a warning.
+  fn set_backdrop(&self, material: BackdropMaterial);
+
+  /// Enables or grays out the close button of the title bar and the `Close` item of the system
+  /// menu, e.g. while a progress window can't be interrupted.
+  ///
+  /// While disabled, <kbd>Alt</kbd>+<kbd>F4</kbd> doesn't request the window to close either.
+  fn set_close_button_enabled(&self, enabled: bool);
+
+  /// Shows `menu` as a popup at `position`, relative to the client area of the window, or at the
+  /// cursor if `position` is `None`.
+  ///
+  /// Returns once the popup is closed. Picking an item sends an
+  /// [`Event::MenuEvent`](crate::event::Event::MenuEvent) with the `ContextMenu` origin through
+  /// the event loop, and native items act on the window like in its menu bar.
+  fn show_context_menu(&self, menu: &ContextMenu, position: Option<Position>);
+
+  /// Sets keyboard shortcuts of the window which don't belong to a menu, replacing the previous
+  /// ones. An empty slice removes them.
+  ///
+  /// Each shortcut sends an [`Event::MenuEvent`](crate::event::Event::MenuEvent) with its id and
+  /// the `MenuBar` origin while the window, or one of its child windows, has the focus, like the
+  /// accelerators of menu items do. The accelerators of the menu bar take precedence.
+  fn set_accelerators(&self, accelerators: &[(MenuId, Accelerator)]);
 }
 
 impl WindowExtWindows for Window {
@@ -130,9 +283,7 @@ impl WindowExtWindows for Window {
 
   #[inline]
   fn set_enable(&self, enabled: bool) {
-    unsafe {
-      EnableWindow(self.window.hwnd(), enabled);
-    }
+    self.window.set_enable(enabled)
   }
 
   #[inline]
@@ -145,6 +296,11 @@ impl WindowExtWindows for Window {
     self.window.theme()
   }
 
+  #[inline]
+  fn set_theme(&self, theme: Option<Theme>) {
+    self.window.set_theme(theme)
+  }
+
   #[inline]
   fn reset_dead_keys(&self) {
     self.window.reset_dead_keys();
@@ -155,10 +311,57 @@ impl WindowExtWindows for Window {
     self.window.begin_resize_drag(edge, button, x, y)
   }
 
+  #[inline]
+  fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+    self.window.drag_resize_window(direction)
+  }
+
   #[inline]
   fn set_skip_taskbar(&self, skip: bool) {
     self.window.set_skip_taskbar(skip);
   }
+
+  #[inline]
+  fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
+    self.window.cursor_position()
+  }
+
+  #[inline]
+  fn set_resize_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+    self.window.set_resize_aspect_ratio(aspect_ratio);
+  }
+
+  #[inline]
+  fn set_progress_bar(&self, state: ProgressState, progress: Option<f64>) {
+    self.window.set_progress_bar(state, progress);
+  }
+
+  #[inline]
+  fn set_overlay_icon(&self, icon: Option<Icon>, description: Option<&str>) {
+    self.window.set_overlay_icon(icon, description);
+  }
+
+  #[inline]
+  fn set_backdrop(&self, material: BackdropMaterial) {
+    self.window.set_backdrop(material);
+  }
+
+  #[inline]
+  fn set_close_button_enabled(&self, enabled: bool) {
+    self.window.set_close_button_enabled(enabled);
+  }
+
+  #[inline]
+  fn show_context_menu(&self, menu: &ContextMenu, position: Option<Position>) {
+    self
+      .window
+      .show_context_menu(&menu.0.menu_platform, position)
+  }
+
+  #[inline]
+  fn set_accelerators(&self, accelerators: &[(MenuId, Accelerator)]) {
+    self.window.set_accelerators(accelerators);
+  }
 }
 
 /// Additional methods on `WindowBuilder` that are specific to Windows.
@@ -264,6 +467,587 @@ impl WindowBuilderExtWindows for WindowBuilder {
   }
 }
 
+/// A submenu added with [`MenuExtWindows::add_submenu_with_handle`].
+///
+/// The submenu is addressed by its position in the parent menu, so the handle refers to another
+/// item once items are inserted or removed before it.
+#[derive(Debug, Clone, Copy)]
+pub struct Submenu(crate::platform_impl::Submenu);
+
+impl Submenu {
+  /// Enables or grays out the submenu, e.g. a "Recent Files" submenu with no entries.
+  ///
+  /// A submenu of a menu bar is redrawn the next time the menu bar is.
+  pub fn set_enabled(&mut self, enabled: bool) {
+    self.0.set_enabled(enabled)
+  }
+
+  /// Shows or hides a checkmark next to the title of the submenu, e.g. when the submenu toggles
+  /// a feature while still exposing its options.
+  ///
+  /// Opening the submenu doesn't toggle the checkmark or send any event.
+  pub fn set_selected(&mut self, selected: bool) {
+    self.0.set_selected(selected)
+  }
+}
+
+/// Additional methods on `CustomMenuItem` that are specific to Windows.
+pub trait CustomMenuItemExtWindows {
+  /// Sets the name announced by screen readers, in place of the visible title.
+  ///
+  /// Useful when the title is an abbreviation or a symbol, e.g. a "⚙" item announced as "Settings".
+  /// The name is attached to the item as an `MSAAMENUINFO` structure in its item data, which the
+  /// system MSAA menu proxy and UI Automation both read. An empty `name` restores the title.
+  fn set_accessible_name(&mut self, name: &str);
+
+  /// Keeps the menu open when this item is clicked, toggling its checkmark in place instead.
+  ///
+  /// The [`MenuEvent`](crate::event::Event::MenuEvent) is still emitted for every toggle, so
+  /// users can flip several options before dismissing the menu. Disabled items are unaffected.
+  fn set_keep_open_on_click(&mut self, keep_open: bool);
+
+  /// Sets whether the menu event keeps being emitted while the item's accelerator is held down.
+  ///
+  /// By default, keyboard auto-repeat of an accelerator emits the event again, which suits
+  /// incremental commands like zooming. Disable it to emit the event once per key press.
+  fn set_accelerator_repeat(&mut self, repeat: bool);
+
+  /// Tags the item with a group, used by [`MenuExtWindows::finalize_groups`] to place separators.
+  fn set_group(&mut self, group: u32);
+
+  /// Hides the item from the menu, or shows it again at its original place.
+  ///
+  /// Unlike a disabled item, a hidden item takes no space in the menu, and its accelerator
+  /// doesn't fire. Items can be shown again in any order.
+  fn set_visible(&mut self, visible: bool);
+
+  /// Replaces the accelerator of the item, or removes it with `None`.
+  ///
+  /// The text next to the title is updated, unless the menu hides it (see [`AcceleratorText`]),
+  /// and so is the accelerator table of the window the menu is attached to.
+  fn set_accelerator(&mut self, accelerator: Option<Accelerator>);
+
+  /// Sets the hint describing this item, e.g. to display it in a status bar.
+  ///
+  /// Highlighting the item emits
+  /// [`Event::MenuItemHighlighted`](crate::event::Event::MenuItemHighlighted) with this hint as
+  /// its `help_text`. An empty `text` removes the hint.
+  fn set_help_text(&mut self, text: &str);
+
+  /// Replaces the checkmark of this item with images, from the content of `.ico` files, shown
+  /// when the item is selected and when it isn't.
+  ///
+  /// The images are rendered at the checkmark size of the system. Returns an error, leaving the
+  /// checkmark unchanged, if either image couldn't be loaded.
+  fn set_check_bitmaps(&mut self, checked: Vec<u8>, unchecked: Vec<u8>) -> Result<(), OsError>;
+}
+
+impl CustomMenuItemExtWindows for CustomMenuItem {
+  #[inline]
+  fn set_accessible_name(&mut self, name: &str) {
+    self.0.set_accessible_name(name)
+  }
+
+  #[inline]
+  fn set_keep_open_on_click(&mut self, keep_open: bool) {
+    self.0.set_keep_open_on_click(keep_open)
+  }
+
+  #[inline]
+  fn set_accelerator_repeat(&mut self, repeat: bool) {
+    self.0.set_accelerator_repeat(repeat)
+  }
+
+  #[inline]
+  fn set_group(&mut self, group: u32) {
+    self.0.set_group(group)
+  }
+
+  #[inline]
+  fn set_visible(&mut self, visible: bool) {
+    self.0.set_visible(visible)
+  }
+
+  #[inline]
+  fn set_accelerator(&mut self, accelerator: Option<Accelerator>) {
+    self.0.set_accelerator(accelerator)
+  }
+
+  #[inline]
+  fn set_help_text(&mut self, text: &str) {
+    self.0.set_help_text(text)
+  }
+
+  #[inline]
+  fn set_check_bitmaps(&mut self, checked: Vec<u8>, unchecked: Vec<u8>) -> Result<(), OsError> {
+    self.0.set_check_bitmaps(checked, unchecked)
+  }
+}
+
+/// Additional methods on `MenuBar` and `ContextMenu` that are specific to Windows.
+pub trait MenuExtWindows {
+  /// Checks the accelerator of every item added afterwards against the active keyboard layout.
+  ///
+  /// Accelerators whose key doesn't exist on the layout, or can only be typed with <kbd>AltGr</kbd>,
+  /// are logged as a warning and reported by [`MenuExtWindows::unreachable_accelerators`].
+  /// Validation is disabled by default, and must be enabled on each submenu that should be checked.
+  fn with_accelerator_validation(self, validate: bool) -> Self
+  where
+    Self: Sized;
+
+  /// Returns the items whose accelerator failed validation, including the ones from submenus.
+  fn unreachable_accelerators(&self) -> Vec<(MenuId, Accelerator)>;
+
+  /// Lays out the menu from right to left, e.g. for Arabic and Hebrew, or back to left to right.
+  ///
+  /// Applies to the items and submenus already added as well as the ones added afterwards.
+  /// Submenus open to the left, and the accelerator text is displayed left of the title.
+  fn set_layout_rtl(&mut self, rtl: bool);
+
+  /// Checks the mnemonic of every item added afterwards against the items already in the menu.
+  ///
+  /// The mnemonic is the letter following a single `&` in the title, compared case-insensitively.
+  /// Items sharing one make <kbd>Alt</kbd> navigation ambiguous, so they are logged as a warning
+  /// and reported by [`MenuExtWindows::mnemonic_conflicts`]. Validation is disabled by default,
+  /// and must be enabled on each submenu that should be checked.
+  fn with_mnemonic_validation(self, validate: bool) -> Self
+  where
+    Self: Sized;
+
+  /// Returns the items whose mnemonic failed validation, including the ones from submenus.
+  fn mnemonic_conflicts(&self) -> Vec<(MenuId, char)>;
+
+  /// Sets when the accelerator of the items added afterwards is displayed next to their title.
+  ///
+  /// The accelerator is registered either way. See [`AcceleratorText`].
+  fn with_accelerator_text(self, accelerator_text: AcceleratorText) -> Self
+  where
+    Self: Sized;
+
+  /// Whether the `Cut`, `Copy`, `Paste` and `SelectAll` native items send an
+  /// [`Event::MenuEvent`](crate::event::Event::MenuEvent) instead of acting on the focused
+  /// control. Disabled by default.
+  ///
+  /// When enabled, the application is responsible for performing the action, e.g. to implement
+  /// its own clipboard handling. The event carries the id returned by
+  /// [`MenuItemExtWindows::id`]. Only applies to the menu attached to a window, this must be set
+  /// before building the window.
+  fn with_edit_item_events(self, send_events: bool) -> Self
+  where
+    Self: Sized;
+
+  /// Lets `filter` decide, each time one of the `Cut`, `Copy`, `Paste` and `SelectAll` native
+  /// items is triggered, whether the application handles it.
+  ///
+  /// `filter` is called on the thread of the window with the id returned by
+  /// [`MenuItemExtWindows::id`], e.g. to check whether the text view of the application has the
+  /// focus. When it returns `true`, the item sends an
+  /// [`Event::MenuEvent`](crate::event::Event::MenuEvent) with that id and the application is
+  /// responsible for performing the action. Otherwise the item acts on the focused control as
+  /// usual. Has no effect when [`MenuExtWindows::with_edit_item_events`] is enabled. Only
+  /// applies to the menu attached to a window, this must be set before building the window.
+  fn with_edit_item_filter(self, filter: Box<dyn Fn(MenuId) -> bool + Send + Sync>) -> Self
+  where
+    Self: Sized;
+
+  /// Whether the `Hide`, `CloseWindow`, `Quit` and `Minimize` native items send an
+  /// [`Event::MenuEvent`](crate::event::Event::MenuEvent) instead of acting on the window.
+  /// Disabled by default.
+  ///
+  /// When enabled, the application can e.g. ask to save changes before quitting, and then
+  /// perform the action itself. The event carries the id returned by [`MenuItemExtWindows::id`].
+  /// Only applies to the menu attached to a window, this must be set before building the window.
+  fn with_window_item_events(self, send_events: bool) -> Self
+  where
+    Self: Sized;
+
+  /// Whether the system beep plays when a key that matches no mnemonic is pressed while the
+  /// menu is open. Enabled by default.
+  ///
+  /// When disabled, the key is ignored and the menu stays open, unless no item is highlighted,
+  /// in which case the menu is closed. Only applies to the menu attached to a window, this must
+  /// be set before building the window.
+  fn with_beep_on_invalid_input(self, beep: bool) -> Self
+  where
+    Self: Sized;
+
+  /// Inserts a separator between adjacent items tagged with different groups.
+  ///
+  /// See [`CustomMenuItemExtWindows::set_group`]. Items without a group are skipped, and existing
+  /// separators are kept. Separators inserted by a previous call are removed first, so this can be
+  /// called again after the menu changed without duplicating them.
+  fn finalize_groups(&mut self);
+
+  /// Sends [`Event::MenuWillOpen`](crate::event::Event::MenuWillOpen) with `menu_id` right before
+  /// this menu opens as a submenu or a popup, or stops sending it when `menu_id` is `None`.
+  ///
+  /// Keep a clone of this menu to clear and populate it when the event is received, e.g. for a
+  /// "Window" submenu listing the open windows. Each nested submenu sends its own event. The
+  /// event is handled before the menu is drawn, unless the menu is opened from the event loop
+  /// callback, e.g. with [`WindowExtWindows::show_context_menu`], in which case it is only
+  /// received after the menu is closed.
+  fn set_will_open_event(&mut self, menu_id: Option<MenuId>);
+
+  /// Appends a label that can't be clicked, introducing the items that follow it, e.g. "Recent".
+  ///
+  /// The label is displayed in bold. It can't be clicked, never sends an event and is skipped by
+  /// [`MenuExtWindows::highlight_next`] and [`MenuExtWindows::highlight_previous`]. Hovering it
+  /// doesn't emit [`Event::MenuItemHighlighted`](crate::event::Event::MenuItemHighlighted) with
+  /// an item. Every `&` is displayed literally.
+  fn add_section_header(&mut self, label: &str);
+
+  /// Registers a keyboard shortcut that sends a [`MenuEvent`](crate::event::Event::MenuEvent)
+  /// with `menu_id`, like the accelerator of an item, without adding a visible item to the menu.
+  fn add_accelerator_only(&mut self, menu_id: MenuId, accelerator: Accelerator);
+
+  /// Returns the number of items in the menu, including separators and submenus.
+  fn item_count(&self) -> u32;
+
+  /// Returns the items of the menu, in the order they are displayed.
+  ///
+  /// Items hidden with [`CustomMenuItemExtWindows::set_visible`] aren't part of the menu.
+  fn items(&self) -> Vec<MenuEntry>;
+
+  /// Appends an item like `add_item`, displaying every `&` of its title literally instead of
+  /// underlining the next character as a mnemonic.
+  fn add_item_escaped(&mut self, item: MenuItemAttributes<'_>) -> CustomMenuItem;
+
+  /// Appends an item like `add_item`, or returns the error of the system if it can't be added.
+  ///
+  /// `add_item` logs the error as a warning and returns an item that doesn't exist in the menu.
+  fn try_add_item(&mut self, item: MenuItemAttributes<'_>) -> Result<CustomMenuItem, OsError>;
+
+  /// Inserts an item before the item at `index`, or at the end of the menu if `index` is out of
+  /// range.
+  fn insert_item_at(&mut self, index: u32, item: MenuItemAttributes<'_>) -> CustomMenuItem;
+
+  /// Appends an item drawn by `draw`, in a space of `size` pixels, e.g. to give it a colored
+  /// background or a second line of text.
+  ///
+  /// `draw` is called on the thread of the window whenever the item is painted, e.g. when it gets
+  /// highlighted. Without it, the item is a regular item.
+  fn add_owner_drawn_item(
+    &mut self,
+    item: MenuItemAttributes<'_>,
+    size: PhysicalSize<u32>,
+    draw: Option<Box<dyn Fn(&MenuItemDrawContext) + Send + Sync>>,
+  ) -> CustomMenuItem;
+
+  /// Adds a submenu like `add_submenu`, returning a handle to change its state afterwards.
+  fn add_submenu_with_handle(&mut self, title: &str, enabled: bool, submenu: Self) -> Submenu
+  where
+    Self: Sized;
+
+  /// Appends a group of radio items, returned in the same order.
+  ///
+  /// Selecting one of them with [`CustomMenuItem::set_selected`] draws a bullet next to it and
+  /// deselects the other items of the group, but not the items around it, so several groups can
+  /// share a menu. The items of a group must stay next to each other.
+  fn add_radio_item_group(&mut self, items: &[(MenuId, &str)]) -> Vec<CustomMenuItem>;
+
+  /// Removes `item`, as returned by `add_item`, from the menu.
+  ///
+  /// Its accelerator is unregistered, including from the window the menu is attached to, and it
+  /// no longer sends events. Does nothing if the item is not in the menu.
+  fn remove_item(&mut self, item: &CustomMenuItem);
+
+  /// Creates a copy of this menu that stays in sync with it, e.g. to show the same menu bar on
+  /// a secondary window.
+  ///
+  /// Changing the title, enabled or selected state (or any [`CustomMenuItemExtWindows`] state) of
+  /// a [`CustomMenuItem`] returned while building either menu updates the item in both, and so does
+  /// toggling an item that keeps the menu open. Updates are applied immediately on the calling
+  /// thread. Items added to either menu after mirroring are not copied to the other one.
+  /// A menu can be mirrored several times, all copies share the same state.
+  fn mirror(&self) -> Self
+  where
+    Self: Sized;
+
+  /// Creates an independent copy of this menu, with the same items in their current state.
+  ///
+  /// Unlike [`MenuExtWindows::mirror`], updating a [`CustomMenuItem`] of this menu doesn't update
+  /// the copy. The state attached to item ids, like accelerators or [`CustomMenuItemExtWindows`]
+  /// options, is still shared by both menus.
+  fn duplicate(&self) -> Self
+  where
+    Self: Sized;
+
+  /// Shows this menu as a popup next to `anchor`, an item of another menu of `window`.
+  ///
+  /// The popup opens below items of the menu bar and beside items of a popup menu that is
+  /// currently open, on whichever side has the most room in the work area of the monitor.
+  /// Clicks on its items are reported to `window`.
+  ///
+  /// Fails if `anchor` is not currently displayed.
+  fn track_popup_at_item(&self, window: &Window, anchor: &CustomMenuItem) -> Result<(), OsError>;
+
+  /// Moves the highlight to the next item of this menu, e.g. for gamepad or remote navigation.
+  ///
+  /// Disabled items and separators are skipped, and the highlight wraps around to the first
+  /// item after the last one. Highlights the first item if none is highlighted.
+  /// `window` must be the window showing the menu.
+  fn highlight_next(&self, window: &Window);
+
+  /// Moves the highlight to the previous item of this menu.
+  ///
+  /// See [`MenuExtWindows::highlight_next`].
+  fn highlight_previous(&self, window: &Window);
+
+  /// Triggers the highlighted item of this menu as if it was clicked.
+  ///
+  /// The menu is closed and an [`Event::MenuEvent`](crate::event::Event::MenuEvent) is sent,
+  /// unless the item keeps the menu open (see [`CustomMenuItemExtWindows::set_keep_open_on_click`]),
+  /// in which case it is toggled instead of closing the menu. Does nothing if no item is
+  /// highlighted, or if the highlighted item is disabled or opens a submenu.
+  fn activate_highlighted(&self, window: &Window);
+}
+
+macro_rules! impl_menu_ext_windows {
+  ($menu:ty) => {
+    impl MenuExtWindows for $menu {
+      #[inline]
+      fn with_accelerator_validation(mut self, validate: bool) -> Self {
+        self.0.menu_platform.set_validate_accelerators(validate);
+        self
+      }
+
+      #[inline]
+      fn with_edit_item_events(mut self, send_events: bool) -> Self {
+        self.0.menu_platform.set_edit_item_events(send_events);
+        self
+      }
+
+      #[inline]
+      fn with_edit_item_filter(
+        mut self,
+        filter: Box<dyn Fn(MenuId) -> bool + Send + Sync>,
+      ) -> Self {
+        self.0.menu_platform.set_edit_item_filter(filter);
+        self
+      }
+
+      #[inline]
+      fn with_window_item_events(mut self, send_events: bool) -> Self {
+        self.0.menu_platform.set_window_item_events(send_events);
+        self
+      }
+
+      #[inline]
+      fn with_beep_on_invalid_input(mut self, beep: bool) -> Self {
+        self.0.menu_platform.set_suppress_beep(!beep);
+        self
+      }
+
+      #[inline]
+      fn with_accelerator_text(mut self, accelerator_text: AcceleratorText) -> Self {
+        self.0.menu_platform.set_accelerator_text(accelerator_text);
+        self
+      }
+
+      #[inline]
+      fn unreachable_accelerators(&self) -> Vec<(MenuId, Accelerator)> {
+        self.0.menu_platform.unreachable_accelerators()
+      }
+
+      #[inline]
+      fn set_layout_rtl(&mut self, rtl: bool) {
+        self.0.menu_platform.set_layout_rtl(rtl)
+      }
+
+      #[inline]
+      fn with_mnemonic_validation(mut self, validate: bool) -> Self {
+        self.0.menu_platform.set_validate_mnemonics(validate);
+        self
+      }
+
+      #[inline]
+      fn mnemonic_conflicts(&self) -> Vec<(MenuId, char)> {
+        self.0.menu_platform.mnemonic_conflicts()
+      }
+
+      #[inline]
+      fn finalize_groups(&mut self) {
+        self.0.menu_platform.finalize_groups()
+      }
+
+      #[inline]
+      fn set_will_open_event(&mut self, menu_id: Option<MenuId>) {
+        self.0.menu_platform.set_will_open_id(menu_id)
+      }
+
+      #[inline]
+      fn add_section_header(&mut self, label: &str) {
+        self.0.menu_platform.add_section_header(label)
+      }
+
+      #[inline]
+      fn add_accelerator_only(&mut self, menu_id: MenuId, accelerator: Accelerator) {
+        self
+          .0
+          .menu_platform
+          .add_accelerator_only(menu_id, accelerator)
+      }
+
+      #[inline]
+      fn item_count(&self) -> u32 {
+        self.0.menu_platform.item_count()
+      }
+
+      #[inline]
+      fn items(&self) -> Vec<MenuEntry> {
+        self.0.menu_platform.items()
+      }
+
+      #[inline]
+      fn add_item_escaped(&mut self, item: MenuItemAttributes<'_>) -> CustomMenuItem {
+        self.0.menu_platform.add_item_escaped(
+          item.id,
+          item.title,
+          item.keyboard_accelerator,
+          item.enabled,
+          item.selected,
+          self.0.menu_type,
+        )
+      }
+
+      #[inline]
+      fn try_add_item(&mut self, item: MenuItemAttributes<'_>) -> Result<CustomMenuItem, OsError> {
+        self.0.menu_platform.try_add_item(
+          item.id,
+          item.title,
+          item.keyboard_accelerator,
+          item.enabled,
+          item.selected,
+          self.0.menu_type,
+        )
+      }
+
+      #[inline]
+      fn insert_item_at(&mut self, index: u32, item: MenuItemAttributes<'_>) -> CustomMenuItem {
+        self.0.menu_platform.insert_item_at(
+          index,
+          item.id,
+          item.title,
+          item.keyboard_accelerator,
+          item.enabled,
+          item.selected,
+          self.0.menu_type,
+        )
+      }
+
+      #[inline]
+      fn add_owner_drawn_item(
+        &mut self,
+        item: MenuItemAttributes<'_>,
+        size: PhysicalSize<u32>,
+        draw: Option<Box<dyn Fn(&MenuItemDrawContext) + Send + Sync>>,
+      ) -> CustomMenuItem {
+        self.0.menu_platform.add_owner_drawn_item(
+          item.id,
+          item.title,
+          item.keyboard_accelerator,
+          item.enabled,
+          item.selected,
+          size,
+          draw,
+          self.0.menu_type,
+        )
+      }
+
+      #[inline]
+      fn add_submenu_with_handle(&mut self, title: &str, enabled: bool, submenu: Self) -> Submenu {
+        Submenu(
+          self
+            .0
+            .menu_platform
+            .add_submenu(title, enabled, submenu.0.menu_platform),
+        )
+      }
+
+      #[inline]
+      fn add_radio_item_group(&mut self, items: &[(MenuId, &str)]) -> Vec<CustomMenuItem> {
+        self
+          .0
+          .menu_platform
+          .add_radio_item_group(items, self.0.menu_type)
+      }
+
+      #[inline]
+      fn remove_item(&mut self, item: &CustomMenuItem) {
+        self.0.menu_platform.remove_item(item)
+      }
+
+      #[inline]
+      fn mirror(&self) -> Self {
+        Self(crate::menu::Menu {
+          menu_platform: self.0.menu_platform.mirror(self.0.menu_type),
+          menu_type: self.0.menu_type,
+        })
+      }
+
+      #[inline]
+      fn duplicate(&self) -> Self {
+        Self(crate::menu::Menu {
+          menu_platform: self.0.menu_platform.duplicate(self.0.menu_type),
+          menu_type: self.0.menu_type,
+        })
+      }
+
+      #[inline]
+      fn track_popup_at_item(
+        &self,
+        window: &Window,
+        anchor: &CustomMenuItem,
+      ) -> Result<(), OsError> {
+        self
+          .0
+          .menu_platform
+          .track_popup_at_item(window.window.hwnd(), anchor)
+      }
+
+      #[inline]
+      fn highlight_next(&self, window: &Window) {
+        self.0.menu_platform.highlight_next(window.window.hwnd())
+      }
+
+      #[inline]
+      fn highlight_previous(&self, window: &Window) {
+        self
+          .0
+          .menu_platform
+          .highlight_previous(window.window.hwnd())
+      }
+
+      #[inline]
+      fn activate_highlighted(&self, window: &Window) {
+        self
+          .0
+          .menu_platform
+          .activate_highlighted(window.window.hwnd())
+      }
+    }
+  };
+}
+
+impl_menu_ext_windows!(MenuBar);
+impl_menu_ext_windows!(ContextMenu);
+
+/// Additional methods on `MenuItem` that are specific to Windows.
+pub trait MenuItemExtWindows {
+  /// Returns the stable id reported by the events of this native item, see
+  /// [`MenuExtWindows::with_edit_item_events`] and [`MenuExtWindows::with_window_item_events`].
+  ///
+  /// Returns `None` for the items that never send events.
+  fn id(&self) -> Option<MenuId>;
+}
+
+impl MenuItemExtWindows for MenuItem {
+  #[inline]
+  fn id(&self) -> Option<MenuId> {
+    crate::platform_impl::native_item_id(self)
+  }
+}
+
 /// Additional methods on `MonitorHandle` that are specific to Windows.
 pub trait MonitorHandleExtWindows {
   /// Returns the name of the monitor adapter specific to the Win32 API.
@@ -1,6 +1,8 @@
 // Copyright 2019-2021 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::clipboard::ClipboardImage;
+
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
 impl Clipboard {
@@ -8,4 +10,8 @@ impl Clipboard {
   pub(crate) fn read_text(&self) -> Option<String> {
     None
   }
+  pub(crate) fn write_image(&mut self, _rgba: &[u8], _width: u32, _height: u32) {}
+  pub(crate) fn read_image(&self) -> Option<ClipboardImage> {
+    None
+  }
 }
@@ -103,7 +103,15 @@ impl MenuItemAttributes {
   pub fn set_enabled(&mut self, _is_enabled: bool) {}
   pub fn set_title(&mut self, _title: &str) {}
   pub fn set_selected(&mut self, _is_selected: bool) {}
-  pub fn set_icon(&mut self, _icon: Vec<u8>) {}
+  pub fn is_enabled(&self) -> bool {
+    false
+  }
+  pub fn is_selected(&self) -> bool {
+    false
+  }
+  pub fn set_icon(&mut self, _icon: Vec<u8>) -> Result<(), crate::error::OsError> {
+    Err(os_error!(OsError))
+  }
 }
 
 pub struct EventLoop<T: 'static> {
@@ -587,6 +595,10 @@ impl Window {
 
   pub fn set_resizable(&self, _resizeable: bool) {}
 
+  pub fn set_minimizable(&self, _minimizable: bool) {}
+
+  pub fn set_maximizable(&self, _maximizable: bool) {}
+
   pub fn set_minimized(&self, _minimized: bool) {}
 
   pub fn set_maximized(&self, _maximized: bool) {}
@@ -622,10 +634,14 @@ impl Window {
 
   pub fn set_always_on_top(&self, _always_on_top: bool) {}
 
+  pub fn set_opacity(&self, _opacity: f64) {}
+
   pub fn set_window_icon(&self, _window_icon: Option<crate::icon::Icon>) {}
 
   pub fn set_ime_position(&self, _position: Position) {}
 
+  pub fn set_ime_allowed(&self, _allowed: bool) {}
+
   pub fn request_user_attention(&self, _request_type: Option<window::UserAttentionType>) {}
 
   pub fn hide_menu(&self) {}
@@ -645,7 +661,7 @@ impl Window {
     ))
   }
 
-  pub fn set_cursor_grab(&self, _: bool) -> Result<(), error::ExternalError> {
+  pub fn set_cursor_grab(&self, _: window::CursorGrabMode) -> Result<(), error::ExternalError> {
     Err(error::ExternalError::NotSupported(
       error::NotSupportedError::new(),
     ))
@@ -712,6 +728,10 @@ impl MonitorHandle {
     (0, 0).into()
   }
 
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    (self.position(), self.size())
+  }
+
   pub fn scale_factor(&self) -> f64 {
     let config = CONFIG.read().unwrap();
     config
@@ -720,6 +740,10 @@ impl MonitorHandle {
       .unwrap_or(1.0)
   }
 
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    None
+  }
+
   pub fn video_modes(&self) -> impl Iterator<Item = monitor::VideoMode> {
     let size = self.size().into();
     let mut v = Vec::new();
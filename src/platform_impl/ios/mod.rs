@@ -148,7 +148,17 @@ impl MenuItemAttributes {
   pub fn set_enabled(&mut self, _is_enabled: bool) {}
   pub fn set_title(&mut self, _title: &str) {}
   pub fn set_selected(&mut self, _is_selected: bool) {}
-  pub fn set_icon(&mut self, _icon: Vec<u8>) {}
+  pub fn is_enabled(&self) -> bool {
+    false
+  }
+  pub fn is_selected(&self) -> bool {
+    false
+  }
+  pub fn set_icon(&mut self, _icon: Vec<u8>) -> Result<(), crate::error::OsError> {
+    Err(os_error!(OsError::CreationError(
+      "Menu item icons are not supported"
+    )))
+  }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -169,12 +179,14 @@ unsafe impl Sync for DeviceId {}
 
 #[non_exhaustive]
 #[derive(Debug)]
-pub enum OsError {}
+pub enum OsError {
+  CreationError(&'static str),
+}
 
 impl fmt::Display for OsError {
-  fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
-      _ => unreachable!(),
+      OsError::CreationError(e) => f.pad(e),
     }
   }
 }
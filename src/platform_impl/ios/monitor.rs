@@ -229,6 +229,10 @@ impl Inner {
     }
   }
 
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    (self.position(), self.size())
+  }
+
   pub fn scale_factor(&self) -> f64 {
     unsafe {
       let scale: CGFloat = msg_send![self.ui_screen(), nativeScale];
@@ -236,6 +240,19 @@ impl Inner {
     }
   }
 
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    unsafe {
+      let os_capabilities = app_state::os_capabilities();
+      if os_capabilities.maximum_frames_per_second {
+        let refresh_rate: NSInteger = msg_send![self.uiscreen, maximumFramesPerSecond];
+        Some(refresh_rate as u32 * 1000)
+      } else {
+        // see `VideoMode::retained_new`
+        Some(60_000)
+      }
+    }
+  }
+
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     let mut modes = BTreeSet::new();
     unsafe {
@@ -25,7 +25,10 @@ use crate::{
     },
     monitor, view, EventLoopWindowTarget, Menu, MonitorHandle,
   },
-  window::{CursorIcon, Fullscreen, UserAttentionType, WindowAttributes, WindowId as RootWindowId},
+  window::{
+    CursorGrabMode, CursorIcon, Fullscreen, UserAttentionType, WindowAttributes,
+    WindowId as RootWindowId,
+  },
 };
 
 pub struct Inner {
@@ -169,6 +172,14 @@ impl Inner {
     warn!("`Window::set_resizable` is ignored on iOS")
   }
 
+  pub fn set_minimizable(&self, _minimizable: bool) {
+    warn!("`Window::set_minimizable` is ignored on iOS")
+  }
+
+  pub fn set_maximizable(&self, _maximizable: bool) {
+    warn!("`Window::set_maximizable` is ignored on iOS")
+  }
+
   pub fn scale_factor(&self) -> f64 {
     unsafe {
       let hidpi: CGFloat = msg_send![self.view, contentScaleFactor];
@@ -184,7 +195,7 @@ impl Inner {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
 
-  pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
+  pub fn set_cursor_grab(&self, _mode: CursorGrabMode) -> Result<(), ExternalError> {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
 
@@ -288,6 +299,10 @@ impl Inner {
     warn!("`Window::set_always_on_top` is ignored on iOS")
   }
 
+  pub fn set_opacity(&self, _opacity: f64) {
+    warn!("`Window::set_opacity` is ignored on iOS")
+  }
+
   pub fn set_window_icon(&self, _icon: Option<Icon>) {
     warn!("`Window::set_window_icon` is ignored on iOS")
   }
@@ -296,6 +311,10 @@ impl Inner {
     warn!("`Window::set_ime_position` is ignored on iOS")
   }
 
+  pub fn set_ime_allowed(&self, _allowed: bool) {
+    warn!("`Window::set_ime_allowed` is ignored on iOS")
+  }
+
   pub fn request_user_attention(&self, _request_type: Option<UserAttentionType>) {
     warn!("`Window::request_user_attention` is ignored on iOS")
   }
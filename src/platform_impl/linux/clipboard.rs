@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use gdk::Atom;
+use gdk_pixbuf::{Colorspace, Pixbuf};
 use gtk::{TargetEntry, TargetFlags};
 
+use crate::clipboard::ClipboardImage;
+
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
 
@@ -46,4 +49,47 @@ impl Clipboard {
 
     None
   }
+
+  pub(crate) fn write_image(&mut self, rgba: &[u8], width: u32, height: u32) {
+    let display = gdk::Display::default().unwrap();
+    let clipboard = gtk::Clipboard::default(&display).unwrap();
+
+    let pixbuf = Pixbuf::from_mut_slice(
+      rgba.to_vec(),
+      Colorspace::Rgb,
+      true,
+      8,
+      width as i32,
+      height as i32,
+      width as i32 * 4,
+    );
+    clipboard.set_image(&pixbuf);
+  }
+
+  pub(crate) fn read_image(&self) -> Option<ClipboardImage> {
+    let display = gdk::Display::default().unwrap();
+    let clipboard = gtk::Clipboard::default(&display).unwrap();
+
+    let pixbuf = clipboard.wait_for_image()?;
+    let (width, height) = (pixbuf.width() as usize, pixbuf.height() as usize);
+    let channels = pixbuf.n_channels() as usize;
+    let row_stride = pixbuf.rowstride() as usize;
+    let has_alpha = pixbuf.has_alpha();
+    let pixels = unsafe { pixbuf.pixels() };
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+      for x in 0..width {
+        let pixel = &pixels[y * row_stride + x * channels..];
+        rgba.extend_from_slice(&pixel[..3]);
+        rgba.push(if has_alpha { pixel[3] } else { u8::MAX });
+      }
+    }
+
+    Some(ClipboardImage {
+      width: width as u32,
+      height: height as u32,
+      rgba,
+    })
+  }
 }
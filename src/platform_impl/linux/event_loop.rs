@@ -19,7 +19,7 @@ use gtk::{prelude::*, AboutDialog, ApplicationWindow, Inhibit};
 use crate::{
   accelerator::AcceleratorId,
   dpi::{LogicalPosition, LogicalSize},
-  event::{ElementState, Event, MouseButton, StartCause, WindowEvent},
+  event::{ElementState, Event, Ime, MouseButton, StartCause, WindowEvent},
   event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
   keyboard::ModifiersState,
   menu::{MenuItem, MenuType},
@@ -222,6 +222,7 @@ impl<T: 'static> EventLoop<T> {
           },
           WindowRequest::Decorations(decorations) => window.set_decorated(decorations),
           WindowRequest::AlwaysOnTop(always_on_top) => window.set_keep_above(always_on_top),
+          WindowRequest::Opacity(opacity) => window.set_opacity(opacity.clamp(0.0, 1.0)),
           WindowRequest::WindowIcon(window_icon) => {
             if let Some(icon) = window_icon {
               window.set_icon(Some(&icon.inner.into()));
@@ -618,6 +619,34 @@ impl<T: 'static> EventLoop<T> {
               }
             });
 
+            let send_ime = {
+              let tx_clone = event_tx.clone();
+              move |ime: Ime| {
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::Ime(ime),
+                }) {
+                  log::warn!("Failed to send IME event to event channel: {}", e);
+                }
+              }
+            };
+            let send_ime_clone = send_ime.clone();
+            ime.connect_preedit_start(move |_| send_ime_clone(Ime::Enabled));
+            let send_ime_clone = send_ime.clone();
+            ime.connect_preedit_changed(move |ime| {
+              let (text, _, cursor) = ime.preedit_string();
+              // the cursor is a position in characters
+              let cursor = text
+                .char_indices()
+                .nth(cursor.max(0) as usize)
+                .map_or(text.len(), |(index, _)| index);
+              send_ime_clone(Ime::Preedit(text.to_string(), Some((cursor, cursor))));
+            });
+            ime.connect_preedit_end(move |_| {
+              send_ime(Ime::Preedit(String::new(), None));
+              send_ime(Ime::Disabled);
+            });
+
             let handler = keyboard_handler.clone();
             window.connect_key_press_event(move |_, event_key| {
               handler(event_key.to_owned(), ElementState::Pressed);
@@ -677,6 +706,7 @@ impl<T: 'static> EventLoop<T> {
                 window_id: Some(RootWindowId(id)),
                 menu_id,
                 origin: MenuType::MenuBar,
+                modifiers: Default::default(),
               }) {
                 log::warn!("Failed to send menu event to event channel: {}", e);
               }
@@ -734,6 +764,7 @@ impl<T: 'static> EventLoop<T> {
               window_id: None,
               menu_id,
               origin: MenuType::ContextMenu,
+              modifiers: Default::default(),
             }) {
               log::warn!("Failed to send status bar event to event channel: {}", e);
             }
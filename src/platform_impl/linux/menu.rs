@@ -89,8 +89,21 @@ impl MenuItemAttributes {
     }
   }
 
+  pub fn is_enabled(&self) -> bool {
+    self.gtk_item.is_sensitive()
+  }
+
+  pub fn is_selected(&self) -> bool {
+    self
+      .gtk_item
+      .downcast_ref::<CheckMenuItem>()
+      .map_or(false, |item| item.is_active())
+  }
+
   // TODO
-  pub fn set_icon(&mut self, _icon: Vec<u8>) {}
+  pub fn set_icon(&mut self, _icon: Vec<u8>) -> Result<(), crate::error::OsError> {
+    Err(os_error!(super::OsError))
+  }
 }
 
 impl Default for Menu {
@@ -46,11 +46,37 @@ impl MonitorHandle {
     .to_physical(self.scale_factor())
   }
 
+  #[inline]
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let rect = self.monitor.workarea();
+    let scale_factor = self.scale_factor();
+    (
+      LogicalPosition {
+        x: rect.x,
+        y: rect.y,
+      }
+      .to_physical(scale_factor),
+      LogicalSize {
+        width: rect.width as u32,
+        height: rect.height as u32,
+      }
+      .to_physical(scale_factor),
+    )
+  }
+
   #[inline]
   pub fn scale_factor(&self) -> f64 {
     self.monitor.scale_factor() as f64
   }
 
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    match self.monitor.refresh_rate() {
+      0 => None,
+      rate => Some(rate as u32),
+    }
+  }
+
   #[inline]
   pub fn video_modes(&self) -> Box<dyn Iterator<Item = RootVideoMode>> {
     Box::new(Vec::new().into_iter())
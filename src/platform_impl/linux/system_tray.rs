@@ -74,6 +74,10 @@ impl SystemTray {
     self.app_indicator.set_icon(&icon.to_string_lossy())
   }
 
+  pub fn set_tooltip(&mut self, _tooltip: &str) {
+    // app indicators don't have tooltips
+  }
+
   pub fn set_menu(&mut self, tray_menu: &Menu) {
     let mut menu =
       tray_menu
@@ -19,7 +19,10 @@ use crate::{
   icon::{BadIcon, Icon},
   menu::{MenuId, MenuItem},
   monitor::MonitorHandle as RootMonitorHandle,
-  window::{CursorIcon, Fullscreen, UserAttentionType, WindowAttributes, BORDERLESS_RESIZE_INSET},
+  window::{
+    CursorGrabMode, CursorIcon, Fullscreen, UserAttentionType, WindowAttributes,
+    BORDERLESS_RESIZE_INSET,
+  },
 };
 
 use super::{
@@ -437,6 +440,10 @@ impl Window {
     }
   }
 
+  pub fn set_minimizable(&self, _minimizable: bool) {}
+
+  pub fn set_maximizable(&self, _maximizable: bool) {}
+
   pub fn set_minimized(&self, minimized: bool) {
     if let Err(e) = self
       .window_requests_tx
@@ -514,6 +521,15 @@ impl Window {
     }
   }
 
+  pub fn set_opacity(&self, opacity: f64) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::Opacity(opacity)))
+    {
+      log::warn!("Fail to send opacity request: {}", e);
+    }
+  }
+
   pub fn set_window_icon(&self, window_icon: Option<Icon>) {
     if let Err(e) = self
       .window_requests_tx
@@ -527,6 +543,8 @@ impl Window {
     //TODO
   }
 
+  pub fn set_ime_allowed(&self, _allowed: bool) {}
+
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
     if let Err(e) = self
       .window_requests_tx
@@ -561,8 +579,8 @@ impl Window {
     todo!()
   }
 
-  pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
-    todo!()
+  pub fn set_cursor_grab(&self, _mode: CursorGrabMode) -> Result<(), ExternalError> {
+    Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
 
   pub fn set_cursor_visible(&self, visible: bool) {
@@ -581,7 +599,8 @@ impl Window {
 
   pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
     let screen = self.window.display().default_screen();
-    let window = self.window.window().unwrap();
+    // The `GdkWindow` only exists once the window is realized.
+    let window = self.window.window()?;
     #[allow(deprecated)] // Gtk3 Window only accepts Gdkscreen
     let number = screen.monitor_at_window(&window);
     let handle = MonitorHandle::new(&self.window.display(), number);
@@ -655,6 +674,7 @@ pub enum WindowRequest {
   Fullscreen(Option<Fullscreen>),
   Decorations(bool),
   AlwaysOnTop(bool),
+  Opacity(f64),
   WindowIcon(Option<Icon>),
   UserAttention(Option<UserAttentionType>),
   SetSkipTaskbar(bool),
@@ -8,6 +8,8 @@ use cocoa::{
 };
 use objc::{class, msg_send, sel, sel_impl};
 
+use crate::clipboard::ClipboardImage;
+
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
 
@@ -38,4 +40,10 @@ impl Clipboard {
       }
     }
   }
+
+  pub(crate) fn write_image(&mut self, _rgba: &[u8], _width: u32, _height: u32) {}
+
+  pub(crate) fn read_image(&self) -> Option<ClipboardImage> {
+    None
+  }
 }
@@ -8,7 +8,7 @@ use cocoa::{
 };
 use objc::{
   declare::ClassDecl,
-  runtime::{Class, Object, Sel, NO, YES},
+  runtime::{Class, Object, Sel, BOOL, NO, YES},
 };
 use std::sync::Once;
 
@@ -71,8 +71,26 @@ impl MenuItemAttributes {
     }
   }
 
+  pub fn is_enabled(&self) -> bool {
+    unsafe {
+      let enabled: BOOL = msg_send![self.1, isEnabled];
+      enabled == YES
+    }
+  }
+
+  pub fn is_selected(&self) -> bool {
+    unsafe {
+      let state: isize = msg_send![self.1, state];
+      state == 1
+    }
+  }
+
   // todo: set custom icon to the menu item
-  pub fn set_icon(&mut self, _icon: Vec<u8>) {}
+  pub fn set_icon(&mut self, _icon: Vec<u8>) -> Result<(), crate::error::OsError> {
+    Err(os_error!(super::OsError::CreationError(
+      "Menu item icons are not supported"
+    )))
+  }
 
   // Available only with CustomMenuItemExtMacOS
   pub fn set_native_image(&mut self, icon: NativeImage) {
@@ -447,6 +465,7 @@ fn send_event(this: &Object, origin: MenuType) {
     window_id,
     menu_id: MenuId(*menu_id.0),
     origin,
+    modifiers: Default::default(),
   };
   AppState::queue_event(EventWrapper::StaticEvent(event));
 }
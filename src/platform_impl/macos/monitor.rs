@@ -219,6 +219,34 @@ impl MonitorHandle {
     )
   }
 
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let screen = match self.ns_screen() {
+      Some(screen) => screen,
+      None => return (self.position(), self.size()),
+    };
+    unsafe {
+      let visible_frame = NSScreen::visibleFrame(screen);
+      let scale_factor = self.scale_factor();
+      // `visibleFrame` starts from the bottom of the main display, while the positions of monitors
+      // start from its top
+      let main_height = CGDisplay::main().bounds().size.height;
+      let y = main_height - visible_frame.origin.y - visible_frame.size.height;
+      (
+        PhysicalPosition::from_logical::<_, f64>(
+          (visible_frame.origin.x as f64, y as f64),
+          scale_factor,
+        ),
+        PhysicalSize::from_logical::<_, f64>(
+          (
+            visible_frame.size.width as f64,
+            visible_frame.size.height as f64,
+          ),
+          scale_factor,
+        ),
+      )
+    }
+  }
+
   pub fn scale_factor(&self) -> f64 {
     let screen = match self.ns_screen() {
       Some(screen) => screen,
@@ -227,6 +255,24 @@ impl MonitorHandle {
     unsafe { NSScreen::backingScaleFactor(screen) as f64 }
   }
 
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    unsafe {
+      let mut display_link = std::ptr::null_mut();
+      if CVDisplayLinkCreateWithCGDisplay(self.0, &mut display_link) != kCVReturnSuccess {
+        return None;
+      }
+      let time = CVDisplayLinkGetNominalOutputVideoRefreshPeriod(display_link);
+      CVDisplayLinkRelease(display_link);
+
+      // This value is indefinite if an invalid display link was specified
+      if time.flags & kCVTimeIsIndefinite != 0 || time.timeValue == 0 {
+        return None;
+      }
+
+      Some((time.timeScale as i64 * 1000 / time.timeValue) as u32)
+    }
+  }
+
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     let cv_refresh_rate = unsafe {
       let mut display_link = std::ptr::null_mut();
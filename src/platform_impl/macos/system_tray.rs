@@ -20,7 +20,7 @@ use cocoa::{
     NSStatusBar, NSStatusItem, NSWindow,
   },
   base::{id, nil, NO, YES},
-  foundation::{NSAutoreleasePool, NSData, NSPoint, NSSize},
+  foundation::{NSAutoreleasePool, NSData, NSPoint, NSSize, NSString},
 };
 use objc::{
   declare::ClassDecl,
@@ -125,6 +125,13 @@ impl SystemTray {
     }
   }
 
+  pub fn set_tooltip(&mut self, tooltip: &str) {
+    unsafe {
+      let tooltip = NSString::alloc(nil).init_str(tooltip);
+      let _: () = msg_send![self.ns_status_bar.button(), setToolTip: tooltip];
+    }
+  }
+
   fn create_button_with_icon(&self) {
     const ICON_WIDTH: f64 = 18.0;
     const ICON_HEIGHT: f64 = 18.0;
@@ -7,7 +7,7 @@ use std::{
 };
 
 use cocoa::{
-  appkit::{CGFloat, NSScreen, NSWindow, NSWindowStyleMask},
+  appkit::{CGFloat, NSScreen, NSWindow, NSWindowButton, NSWindowStyleMask},
   base::{id, nil},
   foundation::{NSPoint, NSSize, NSString},
 };
@@ -95,6 +95,27 @@ pub unsafe fn set_level_async(ns_window: id, level: ffi::NSWindowLevel) {
   });
 }
 
+// `setAlphaValue` is not thread-safe
+pub unsafe fn set_alpha_value_async(ns_window: id, alpha: CGFloat) {
+  let ns_window = MainThreadSafe(ns_window);
+  Queue::main().exec_async(move || {
+    ns_window.setAlphaValue_(alpha);
+  });
+}
+
+// `setEnabled:` of the title bar buttons isn't thread-safe
+pub unsafe fn set_titlebar_button_enabled_async(
+  ns_window: id,
+  button: NSWindowButton,
+  enabled: bool,
+) {
+  let ns_window = MainThreadSafe(ns_window);
+  Queue::main().exec_async(move || {
+    let button = ns_window.standardWindowButton_(button);
+    let _: () = msg_send![button, setEnabled: if enabled { YES } else { NO }];
+  });
+}
+
 // `toggleFullScreen` is thread-safe, but our additional logic to account for
 // window styles isn't.
 pub unsafe fn toggle_full_screen_async(
@@ -22,7 +22,7 @@ use objc::{
 use crate::{
   dpi::LogicalPosition,
   event::{
-    DeviceEvent, ElementState, Event, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+    DeviceEvent, ElementState, Event, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
   },
   keyboard::{KeyCode, ModifiersState},
   platform_impl::platform::{
@@ -456,7 +456,7 @@ extern "C" fn set_marked_text(
   this: &mut Object,
   _sel: Sel,
   string: id,
-  _selected_range: NSRange,
+  selected_range: NSRange,
   _replacement_range: NSRange,
 ) {
   trace!("Triggered `setMarkedText`");
@@ -469,14 +469,55 @@ extern "C" fn set_marked_text(
       marked_text_ref.initWithString(string);
     };
 
+    let characters: id = if has_attr != NO {
+      msg_send![string, string]
+    } else {
+      string
+    };
+    let slice = slice::from_raw_parts(characters.UTF8String() as *const c_uchar, characters.len());
+    let preedit = str::from_utf8_unchecked(slice).to_owned();
+    let cursor = utf16_range_to_byte_range(&preedit, selected_range);
+
     let state_ptr: *mut c_void = *this.get_ivar("taoState");
     let state = &mut *(state_ptr as *mut ViewState);
+    let window_id = WindowId(get_window_id(state.ns_window));
+    if !state.in_ime_preedit {
+      AppState::queue_event(EventWrapper::StaticEvent(Event::WindowEvent {
+        window_id,
+        event: WindowEvent::Ime(Ime::Enabled),
+      }));
+    }
+    AppState::queue_event(EventWrapper::StaticEvent(Event::WindowEvent {
+      window_id,
+      event: WindowEvent::Ime(Ime::Preedit(preedit, cursor)),
+    }));
     state.in_ime_preedit = true;
     state.key_triggered_ime = true;
   }
   trace!("Completed `setMarkedText`");
 }
 
+/// Turns a range of UTF-16 units of `text`, as used by `NSString`, into a range of bytes.
+fn utf16_range_to_byte_range(text: &str, range: NSRange) -> Option<(usize, usize)> {
+  if range.location == NSNotFound as NSUInteger {
+    return None;
+  }
+  let byte_offset = |utf16_offset: NSUInteger| {
+    let mut units = 0;
+    text
+      .char_indices()
+      .find(|(_, c)| {
+        let found = units >= utf16_offset as usize;
+        units += c.len_utf16();
+        found
+      })
+      .map_or(text.len(), |(index, _)| index)
+  };
+  let start = byte_offset(range.location);
+  let end = byte_offset(range.location + range.length);
+  Some((start, end))
+}
+
 extern "C" fn unmark_text(this: &mut Object, _sel: Sel) {
   trace!("Triggered `unmarkText`");
   unsafe {
@@ -557,8 +598,21 @@ extern "C" fn insert_text(this: &Object, _sel: Sel, string: id, _replacement_ran
     // We don't need this now, but it's here if that changes.
     //let event: id = msg_send![NSApp(), currentEvent];
 
+    let window_id = WindowId(get_window_id(state.ns_window));
+    if state.in_ime_preedit {
+      for ime in [
+        Ime::Preedit(String::new(), None),
+        Ime::Commit(string.clone()),
+        Ime::Disabled,
+      ] {
+        AppState::queue_event(EventWrapper::StaticEvent(Event::WindowEvent {
+          window_id,
+          event: WindowEvent::Ime(ime),
+        }));
+      }
+    }
     AppState::queue_event(EventWrapper::StaticEvent(Event::WindowEvent {
-      window_id: WindowId(get_window_id(state.ns_window)),
+      window_id,
       event: WindowEvent::ReceivedImeText(string),
     }));
     if state.in_ime_preedit {
@@ -30,7 +30,10 @@ use crate::{
     window_delegate::new_delegate,
     OsError,
   },
-  window::{CursorIcon, Fullscreen, UserAttentionType, WindowAttributes, WindowId as RootWindowId},
+  window::{
+    CursorGrabMode, CursorIcon, Fullscreen, UserAttentionType, WindowAttributes,
+    WindowId as RootWindowId,
+  },
 };
 use cocoa::{
   appkit::{
@@ -221,6 +224,14 @@ fn create_window(
           let _: () = msg_send![button, setHidden: YES];
         }
       }
+      if !attrs.minimizable {
+        let button = ns_window.standardWindowButton_(NSWindowButton::NSWindowMiniaturizeButton);
+        let _: () = msg_send![button, setEnabled: NO];
+      }
+      if !attrs.maximizable {
+        let button = ns_window.standardWindowButton_(NSWindowButton::NSWindowZoomButton);
+        let _: () = msg_send![button, setEnabled: NO];
+      }
       if pl_attrs.movable_by_window_background {
         ns_window.setMovableByWindowBackground_(YES);
       }
@@ -589,6 +600,28 @@ impl UnownedWindow {
     } // Otherwise, we don't change the mask until we exit fullscreen.
   }
 
+  #[inline]
+  pub fn set_minimizable(&self, minimizable: bool) {
+    unsafe {
+      util::set_titlebar_button_enabled_async(
+        *self.ns_window,
+        NSWindowButton::NSWindowMiniaturizeButton,
+        minimizable,
+      );
+    }
+  }
+
+  #[inline]
+  pub fn set_maximizable(&self, maximizable: bool) {
+    unsafe {
+      util::set_titlebar_button_enabled_async(
+        *self.ns_window,
+        NSWindowButton::NSWindowZoomButton,
+        maximizable,
+      );
+    }
+  }
+
   pub fn set_cursor_icon(&self, cursor: CursorIcon) {
     let cursor = util::Cursor::from(cursor);
     if let Some(cursor_access) = self.cursor_state.upgrade() {
@@ -602,9 +635,16 @@ impl UnownedWindow {
   }
 
   #[inline]
-  pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
-    // TODO: Do this for real https://stackoverflow.com/a/40922095/5435443
-    CGDisplay::associate_mouse_and_mouse_cursor_position(!grab)
+  pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
+    let associate_mouse_cursor = match mode {
+      CursorGrabMode::None => true,
+      CursorGrabMode::Locked => false,
+      // TODO: Do this for real https://stackoverflow.com/a/40922095/5435443
+      CursorGrabMode::Confined => {
+        return Err(ExternalError::NotSupported(NotSupportedError::new()))
+      }
+    };
+    CGDisplay::associate_mouse_and_mouse_cursor_position(associate_mouse_cursor)
       .map_err(|status| ExternalError::Os(os_error!(OsError::CGError(status))))
   }
 
@@ -1032,6 +1072,11 @@ impl UnownedWindow {
     unsafe { util::set_level_async(*self.ns_window, level) };
   }
 
+  #[inline]
+  pub fn set_opacity(&self, opacity: f64) {
+    unsafe { util::set_alpha_value_async(*self.ns_window, opacity.clamp(0.0, 1.0) as CGFloat) };
+  }
+
   #[inline]
   pub fn set_window_icon(&self, _icon: Option<Icon>) {
     // macOS doesn't have window icons. Though, there is
@@ -1058,6 +1103,9 @@ impl UnownedWindow {
     }
   }
 
+  #[inline]
+  pub fn set_ime_allowed(&self, _allowed: bool) {}
+
   #[inline]
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
     let ns_request_type = request_type.map(|ty| match ty {
@@ -1100,6 +1148,11 @@ impl UnownedWindow {
 
   #[inline]
   pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
+    // The window has no screen when it's entirely offscreen.
+    let screen: id = unsafe { msg_send![*self.ns_window, screen] };
+    if screen == nil {
+      return None;
+    }
     Some(self.current_monitor_inner())
   }
 
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+  cell::Cell,
   collections::HashMap,
+  ptr,
   sync::{Arc, Mutex},
 };
 
@@ -27,6 +29,14 @@ unsafe impl Sync for AccelHandle {}
 lazy_static! {
   static ref ACCEL_TABLES: Mutex<HashMap<WindowHandle, Arc<AccelTable>>> =
     Mutex::new(HashMap::default());
+  // The tables set with `WindowExtWindows::set_accelerators`, independent of the menus.
+  static ref WINDOW_ACCEL_TABLES: Mutex<HashMap<WindowHandle, Arc<AccelTable>>> =
+    Mutex::new(HashMap::default());
+}
+
+thread_local! {
+  // Whether the message currently going through `TranslateAcceleratorW` is a keyboard auto-repeat.
+  static TRANSLATING_REPEAT: Cell<bool> = Cell::new(false);
 }
 
 /// A Accelerators Table for Windows
@@ -46,6 +56,15 @@ impl AccelTable {
   pub(crate) fn handle(&self) -> HACCEL {
     HACCEL(self.accel.0)
   }
+
+  fn accels(&self) -> Vec<ACCEL> {
+    unsafe {
+      let count = CopyAcceleratorTableW(self.handle(), ptr::null_mut(), 0);
+      let mut accels = vec![ACCEL::default(); count.max(0) as usize];
+      CopyAcceleratorTableW(self.handle(), accels.as_mut_ptr(), count);
+      accels
+    }
+  }
 }
 
 pub(crate) fn register_accel(hwnd: HWND, accel: &[ACCEL]) {
@@ -53,6 +72,30 @@ pub(crate) fn register_accel(hwnd: HWND, accel: &[ACCEL]) {
   table.insert(WindowHandle(hwnd.0), Arc::new(AccelTable::new(accel)));
 }
 
+pub(crate) fn unregister_accel(hwnd: HWND) {
+  let mut table = ACCEL_TABLES.lock().unwrap();
+  table.remove(&WindowHandle(hwnd.0));
+}
+
+/// Replace the accelerators of `hwnd` which don't belong to a menu, removing them if `accel` is
+/// empty.
+pub(crate) fn register_window_accel(hwnd: HWND, accel: &[ACCEL]) {
+  let mut table = WINDOW_ACCEL_TABLES.lock().unwrap();
+  if accel.is_empty() {
+    table.remove(&WindowHandle(hwnd.0));
+  } else {
+    table.insert(WindowHandle(hwnd.0), Arc::new(AccelTable::new(accel)));
+  }
+}
+
+/// Whether `cmd` is one of the accelerators of `hwnd` which don't belong to a menu.
+pub(crate) fn is_window_accel(hwnd: HWND, cmd: u16) -> bool {
+  let table = WINDOW_ACCEL_TABLES.lock().unwrap();
+  table.get(&WindowHandle(hwnd.0)).map_or(false, |table| {
+    table.accels().iter().any(|accel| accel.cmd == cmd)
+  })
+}
+
 impl Drop for AccelTable {
   fn drop(&mut self) {
     unsafe {
@@ -61,7 +104,36 @@ impl Drop for AccelTable {
   }
 }
 
-pub(crate) fn find_accels(hwnd: HWND) -> Option<Arc<AccelTable>> {
-  let table = ACCEL_TABLES.lock().unwrap();
-  table.get(&WindowHandle(hwnd.0)).cloned()
+/// Find the menu accelerator table of `hwnd`, or of its closest ancestor that has one, followed by
+/// the closest table which doesn't belong to a menu.
+pub(crate) fn find_accels(hwnd: HWND) -> Vec<(HWND, Arc<AccelTable>)> {
+  let mut accels = Vec::new();
+  for tables in [&*ACCEL_TABLES, &*WINDOW_ACCEL_TABLES] {
+    let table = tables.lock().unwrap();
+    let mut hwnd = hwnd;
+    while !hwnd.is_invalid() {
+      if let Some(table) = table.get(&WindowHandle(hwnd.0)) {
+        accels.push((hwnd, table.clone()));
+        break;
+      }
+      hwnd = unsafe { GetAncestor(hwnd, GA_PARENT) };
+    }
+  }
+  accels
+}
+
+/// Translate `msg` with the accelerator table of `hwnd`, remembering whether it is an
+/// auto-repeat while the resulting `WM_COMMAND` is being dispatched to `hwnd`.
+pub(crate) fn translate_accelerator(hwnd: HWND, msg: &MSG, table: &AccelTable) -> bool {
+  let is_repeat =
+    matches!(msg.message, WM_KEYDOWN | WM_SYSKEYDOWN) && msg.lParam.0 & (1 << 30) != 0;
+  TRANSLATING_REPEAT.with(|repeat| repeat.set(is_repeat));
+  let translated = unsafe { TranslateAcceleratorW(hwnd, table.handle(), msg) != 0 };
+  TRANSLATING_REPEAT.with(|repeat| repeat.set(false));
+  translated
+}
+
+/// Whether the `WM_COMMAND` being handled was generated by a held-down accelerator.
+pub(crate) fn is_translating_repeat() -> bool {
+  TRANSLATING_REPEAT.with(|repeat| repeat.get())
 }
@@ -1,17 +1,17 @@
 // Copyright 2019-2021 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::clipboard::{ClipboardFormat, FormatId};
-use std::{ffi::OsStr, os::windows::ffi::OsStrExt, ptr};
+use crate::clipboard::{ClipboardFormat, ClipboardImage, FormatId};
+use std::{ffi::OsStr, os::windows::ffi::OsStrExt, ptr, thread, time::Duration};
 use windows::Win32::{
   Foundation::{HANDLE, HWND, PSTR, PWSTR},
   System::{
     DataExchange::{
-      CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard, RegisterClipboardFormatA,
-      SetClipboardData,
+      CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+      RegisterClipboardFormatA, SetClipboardData,
     },
-    Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE},
-    SystemServices::CF_UNICODETEXT,
+    Memory::{GlobalAlloc, GlobalFree, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE},
+    SystemServices::{CF_DIB, CF_DIBV5, CF_UNICODETEXT},
   },
 };
 
@@ -29,22 +29,51 @@ impl Clipboard {
     with_clipboard(|| unsafe {
       let handle = GetClipboardData(CF_UNICODETEXT);
       if handle.0 == 0 {
-        None
-      } else {
-        let unic_str = PWSTR(GlobalLock(handle.0) as *mut _);
-        let mut len = 0;
-        while *unic_str.0.offset(len) != 0 {
-          len += 1;
-        }
-        let utf16_slice = std::slice::from_raw_parts(unic_str.0, len as usize);
-        let result = String::from_utf16(utf16_slice);
-        if let Ok(result) = result {
-          GlobalUnlock(handle.0);
-          return Some(result);
-        }
+        return None;
+      }
+      let unic_str = PWSTR(GlobalLock(handle.0) as *mut _);
+      if unic_str.0.is_null() {
+        return None;
+      }
+      let mut len = 0;
+      while *unic_str.0.offset(len) != 0 {
+        len += 1;
+      }
+      let utf16_slice = std::slice::from_raw_parts(unic_str.0, len as usize);
+      let result = String::from_utf16(utf16_slice).ok();
+      GlobalUnlock(handle.0);
+      result
+    })
+    .flatten()
+  }
+
+  pub(crate) fn write_image(&mut self, rgba: &[u8], width: u32, height: u32) {
+    self.put_formats(&[
+      ClipboardFormat::new("CF_DIBV5", dibv5_from_rgba(rgba, width, height)),
+      ClipboardFormat::new("CF_DIB", dib_from_rgba(rgba, width, height)),
+    ])
+  }
 
-        None
+  pub(crate) fn read_image(&self) -> Option<ClipboardImage> {
+    with_clipboard(|| unsafe {
+      // the system converts between both formats, but the conversion to `CF_DIB` drops alpha
+      let format = if IsClipboardFormatAvailable(CF_DIBV5).as_bool() {
+        CF_DIBV5
+      } else {
+        CF_DIB
+      };
+      let handle = GetClipboardData(format);
+      if handle.0 == 0 {
+        return None;
+      }
+      let locked = GlobalLock(handle.0) as *const u8;
+      if locked.is_null() {
+        return None;
       }
+      let dib = std::slice::from_raw_parts(locked, GlobalSize(handle.0));
+      let image = rgba_from_dib(dib);
+      GlobalUnlock(handle.0);
+      image
     })
     .flatten()
   }
@@ -54,7 +83,6 @@ impl Clipboard {
       EmptyClipboard();
 
       for format in formats {
-        let handle = make_handle(format);
         let format_id = match get_format_id(format.identifier) {
           Some(id) => id,
           None => {
@@ -62,6 +90,16 @@ impl Clipboard {
             continue;
           }
         };
+        let handle = match make_handle(format) {
+          Some(handle) => handle,
+          None => {
+            warn!(
+              "failed to allocate clipboard for fmt {}",
+              &format.identifier
+            );
+            continue;
+          }
+        };
         let result = SetClipboardData(format_id, handle);
         if result.0 == 0 {
           println!(
@@ -69,6 +107,8 @@ impl Clipboard {
             &format.identifier,
             windows::core::Error::from_win32().code().0
           );
+          // the memory is only owned by the system once the data is set
+          GlobalFree(handle.0);
         }
       }
     });
@@ -100,28 +140,192 @@ fn register_identifier(ident: &str) -> Option<u32> {
   }
 }
 
-unsafe fn make_handle(format: &ClipboardFormat) -> HANDLE {
-  HANDLE(if format.identifier == ClipboardFormat::TEXT {
+unsafe fn make_handle(format: &ClipboardFormat) -> Option<HANDLE> {
+  if format.identifier == ClipboardFormat::TEXT {
     let s: &OsStr = std::str::from_utf8_unchecked(&format.data).as_ref();
     let wstr: Vec<u16> = s.encode_wide().chain(Some(0)).collect();
     let handle = GlobalAlloc(GMEM_MOVEABLE, wstr.len() * std::mem::size_of::<u16>());
     let locked = PWSTR(GlobalLock(handle) as *mut _);
+    if locked.0.is_null() {
+      GlobalFree(handle);
+      return None;
+    }
     ptr::copy_nonoverlapping(wstr.as_ptr(), locked.0, wstr.len());
     GlobalUnlock(handle);
-    handle
+    Some(HANDLE(handle))
   } else {
     let handle = GlobalAlloc(GMEM_MOVEABLE, format.data.len() * std::mem::size_of::<u8>());
     let locked = PSTR(GlobalLock(handle) as *mut _);
+    if locked.0.is_null() {
+      GlobalFree(handle);
+      return None;
+    }
     ptr::copy_nonoverlapping(format.data.as_ptr(), locked.0, format.data.len());
     GlobalUnlock(handle);
-    handle
+    Some(HANDLE(handle))
+  }
+}
+
+const BITMAPINFOHEADER_SIZE: usize = 40;
+const BITMAPV5HEADER_SIZE: usize = 124;
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const LCS_SRGB: u32 = 0x7352_4742;
+const LCS_GM_IMAGES: u32 = 4;
+// The masks of the red, green, blue and alpha channels in a pixel read as a little-endian `u32`,
+// i.e. stored as BGRA.
+const BGRA_MASKS: [u32; 4] = [0x00ff_0000, 0x0000_ff00, 0x0000_00ff, 0xff00_0000];
+
+// A `BITMAPINFOHEADER` of a bottom-up 32bpp bitmap, with the extra fields of a `BITMAPV5HEADER`
+// if `size` is `BITMAPV5HEADER_SIZE`.
+fn dib_header(width: u32, height: u32, size: usize) -> Vec<u8> {
+  let mut header = Vec::with_capacity(size);
+  header.extend_from_slice(&(size as u32).to_le_bytes());
+  header.extend_from_slice(&(width as i32).to_le_bytes());
+  header.extend_from_slice(&(height as i32).to_le_bytes());
+  header.extend_from_slice(&1u16.to_le_bytes()); // planes
+  header.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+  let compression = match size {
+    BITMAPV5HEADER_SIZE => BI_BITFIELDS,
+    _ => BI_RGB,
+  };
+  header.extend_from_slice(&compression.to_le_bytes());
+  header.extend_from_slice(&(width * height * 4).to_le_bytes());
+  // pixels per meter, colors used and important colors
+  header.resize(BITMAPINFOHEADER_SIZE, 0);
+
+  if size == BITMAPV5HEADER_SIZE {
+    for mask in &BGRA_MASKS {
+      header.extend_from_slice(&mask.to_le_bytes());
+    }
+    header.extend_from_slice(&LCS_SRGB.to_le_bytes());
+    // endpoints and gamma are ignored for sRGB
+    header.resize(BITMAPV5HEADER_SIZE - 16, 0);
+    header.extend_from_slice(&LCS_GM_IMAGES.to_le_bytes());
+    // profile data, profile size and reserved
+    header.resize(BITMAPV5HEADER_SIZE, 0);
+  }
+  header
+}
+
+// Appends the RGBA rows of `rgba` as BGRA rows from bottom to top.
+fn push_bgra_rows(dib: &mut Vec<u8>, rgba: &[u8], width: u32, premultiply: bool) {
+  for row in rgba.chunks_exact(width as usize * 4).rev() {
+    for pixel in row.chunks_exact(4) {
+      let alpha = pixel[3] as u32;
+      let channel = |value: u8| match premultiply {
+        true => ((value as u32 * alpha + 127) / 255) as u8,
+        false => value,
+      };
+      dib.extend_from_slice(&[
+        channel(pixel[2]),
+        channel(pixel[1]),
+        channel(pixel[0]),
+        pixel[3],
+      ]);
+    }
+  }
+}
+
+// A `CF_DIBV5` bitmap with straight alpha, which applications supporting transparency read.
+fn dibv5_from_rgba(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+  let mut dib = dib_header(width, height, BITMAPV5HEADER_SIZE);
+  push_bgra_rows(&mut dib, rgba, width, false);
+  dib
+}
+
+// A `CF_DIB` bitmap with premultiplied alpha, so the transparent parts of the image are black
+// instead of showing leftover colors in applications ignoring the alpha channel.
+fn dib_from_rgba(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+  let mut dib = dib_header(width, height, BITMAPINFOHEADER_SIZE);
+  push_bgra_rows(&mut dib, rgba, width, true);
+  dib
+}
+
+// Reads an uncompressed 24bpp or 32bpp `CF_DIB` or `CF_DIBV5` bitmap.
+fn rgba_from_dib(dib: &[u8]) -> Option<ClipboardImage> {
+  let u32_at = |offset: usize| -> Option<u32> {
+    let bytes = dib.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  };
+  let header_size = u32_at(0)? as usize;
+  let width = u32_at(4)? as i32;
+  let height = u32_at(8)? as i32;
+  let bit_count = u16::from_le_bytes([*dib.get(14)?, *dib.get(15)?]);
+  let compression = u32_at(16)?;
+  if width <= 0 || height == 0 || header_size < BITMAPINFOHEADER_SIZE {
+    return None;
+  }
+
+  let masks = match compression {
+    BI_RGB => None,
+    // with a plain `BITMAPINFOHEADER`, the masks follow the header
+    BI_BITFIELDS if header_size == BITMAPINFOHEADER_SIZE => Some((
+      [u32_at(40)?, u32_at(44)?, u32_at(48)?],
+      BITMAPINFOHEADER_SIZE + 12,
+    )),
+    BI_BITFIELDS => Some(([u32_at(40)?, u32_at(44)?, u32_at(48)?], header_size)),
+    _ => return None,
+  };
+  if let Some((masks, _)) = masks {
+    if bit_count != 32 || masks[..] != BGRA_MASKS[..3] {
+      return None;
+    }
+  }
+  let pixels_offset = masks.map_or(header_size, |(_, offset)| offset);
+  let bytes_per_pixel = match bit_count {
+    24 => 3,
+    32 => 4,
+    _ => return None,
+  };
+
+  let (width, rows) = (width as usize, height.unsigned_abs() as usize);
+  // rows are padded to 4 bytes
+  let row_size = (width * bytes_per_pixel + 3) & !3;
+  let pixels = dib.get(pixels_offset..pixels_offset + row_size * rows)?;
+  let mut rgba = Vec::with_capacity(width * rows * 4);
+  let mut has_alpha = false;
+  for y in 0..rows {
+    // a positive height means the rows go from bottom to top
+    let row = if height > 0 { rows - 1 - y } else { y };
+    let row = &pixels[row * row_size..row * row_size + width * bytes_per_pixel];
+    for pixel in row.chunks_exact(bytes_per_pixel) {
+      let alpha = if bytes_per_pixel == 4 {
+        pixel[3]
+      } else {
+        u8::MAX
+      };
+      has_alpha |= alpha != 0;
+      rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], alpha]);
+    }
+  }
+  // The fourth byte of 32bpp `BI_RGB` bitmaps is often left to zero instead of holding alpha.
+  if !has_alpha {
+    rgba
+      .chunks_exact_mut(4)
+      .for_each(|pixel| pixel[3] = u8::MAX);
+  }
+
+  Some(ClipboardImage {
+    width: width as u32,
+    height: rows as u32,
+    rgba,
   })
 }
 
 fn with_clipboard<V>(f: impl FnOnce() -> V) -> Option<V> {
   unsafe {
-    if !OpenClipboard(HWND::default()).as_bool() {
-      return None;
+    // Another process may hold the clipboard for a short while, so we retry with a growing delay
+    // before giving up.
+    let mut delay = Duration::from_millis(5);
+    let mut attempts = 5;
+    while !OpenClipboard(HWND::default()).as_bool() {
+      attempts -= 1;
+      if attempts == 0 {
+        return None;
+      }
+      thread::sleep(delay);
+      delay *= 2;
     }
 
     let result = f();
@@ -161,3 +365,26 @@ static STANDARD_FORMATS: &[(u32, &str)] = &[
   (0x0300, "CF_GDIOBJFIRST"),
   (0x03FF, "CF_GDIOBJLAST"),
 ];
+
+#[test]
+fn test_dib_round_trip() {
+  // a 3x2 image, so the 24bpp rows would need padding
+  let rgba: Vec<u8> = vec![
+    255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 0, //
+    10, 20, 30, 255, 40, 50, 60, 255, 70, 80, 90, 255,
+  ];
+
+  let dibv5 = dibv5_from_rgba(&rgba, 3, 2);
+  assert_eq!(dibv5.len(), BITMAPV5HEADER_SIZE + rgba.len());
+  let image = rgba_from_dib(&dibv5).unwrap();
+  assert_eq!((image.width, image.height), (3, 2));
+  assert_eq!(image.rgba, rgba);
+
+  // the plain bitmap is premultiplied
+  let image = rgba_from_dib(&dib_from_rgba(&rgba, 3, 2)).unwrap();
+  assert_eq!(
+    &image.rgba[..12],
+    &[255, 0, 0, 255, 0, 128, 0, 128, 0, 0, 0, 0]
+  );
+  assert_eq!(&image.rgba[12..], &rgba[12..]);
+}
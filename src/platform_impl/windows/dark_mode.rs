@@ -5,6 +5,7 @@
 /// which is inspired by the solution in https://github.com/ysc3839/win32-darkmode
 use windows::Win32::{
   Foundation::{BOOL, HWND, PSTR, PWSTR},
+  Graphics::Dwm::{DwmSetWindowAttribute, DWMWINDOWATTRIBUTE},
   System::LibraryLoader::*,
   UI::{Accessibility::*, Controls::*, WindowsAndMessaging::*},
 };
@@ -14,7 +15,7 @@ use std::ffi::c_void;
 use crate::{platform_impl::platform::util, window::Theme};
 
 lazy_static! {
-    static ref WIN10_BUILD_VERSION: Option<u32> = {
+    pub static ref WIN10_BUILD_VERSION: Option<u32> = {
         // FIXME: RtlGetVersion is a documented windows API,
         // should be part of win32metadata!
 
@@ -101,8 +102,45 @@ pub fn try_theme(hwnd: HWND, preferred_theme: Option<Theme>) -> Theme {
   Theme::Light
 }
 
+/// Redraw the title bar of a window after its theme changed, which DWM only does on the next
+/// activation otherwise.
+pub fn refresh_title_bar(hwnd: HWND) {
+  unsafe {
+    SetWindowPos(
+      hwnd,
+      HWND::default(),
+      0,
+      0,
+      0,
+      0,
+      SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+    );
+  }
+}
+
 fn set_dark_mode_for_window(hwnd: HWND, is_dark_mode: bool) -> bool {
-  // Uses Windows undocumented API SetWindowCompositionAttribute,
+  // The attribute was only documented with Windows 11, and had another number before 20H1.
+  const DWMWA_USE_IMMERSIVE_DARK_MODE_BEFORE_20H1: DWMWINDOWATTRIBUTE = 19;
+  const DWMWA_USE_IMMERSIVE_DARK_MODE: DWMWINDOWATTRIBUTE = 20;
+
+  let attribute = match *WIN10_BUILD_VERSION {
+    Some(v) if v >= 18985 => DWMWA_USE_IMMERSIVE_DARK_MODE,
+    _ => DWMWA_USE_IMMERSIVE_DARK_MODE_BEFORE_20H1,
+  };
+  let is_dark_mode_bigbool: BOOL = is_dark_mode.into();
+  let status = unsafe {
+    DwmSetWindowAttribute(
+      hwnd,
+      attribute,
+      &is_dark_mode_bigbool as *const _ as _,
+      std::mem::size_of_val(&is_dark_mode_bigbool) as _,
+    )
+  };
+  if status.is_ok() {
+    return true;
+  }
+
+  // Otherwise uses Windows undocumented API SetWindowCompositionAttribute,
   // as seen in win32-darkmode example linked at top of file.
 
   type SetWindowCompositionAttribute =
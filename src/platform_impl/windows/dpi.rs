@@ -12,8 +12,9 @@ use windows::Win32::{
 };
 
 use crate::platform_impl::platform::util::{
-  ENABLE_NON_CLIENT_DPI_SCALING, GET_DPI_FOR_MONITOR, GET_DPI_FOR_WINDOW, SET_PROCESS_DPI_AWARE,
-  SET_PROCESS_DPI_AWARENESS, SET_PROCESS_DPI_AWARENESS_CONTEXT,
+  ENABLE_NON_CLIENT_DPI_SCALING, GET_DPI_FOR_MONITOR, GET_DPI_FOR_WINDOW,
+  GET_SYSTEM_METRICS_FOR_DPI, SET_PROCESS_DPI_AWARE, SET_PROCESS_DPI_AWARENESS,
+  SET_PROCESS_DPI_AWARENESS_CONTEXT,
 };
 
 pub fn become_dpi_aware() {
@@ -68,6 +69,19 @@ pub fn dpi_to_scale_factor(dpi: u32) -> f64 {
   dpi as f64 / BASE_DPI as f64
 }
 
+pub unsafe fn system_metrics_for_dpi(index: SYSTEM_METRICS_INDEX, dpi: u32) -> i32 {
+  if let Some(GetSystemMetricsForDpi) = *GET_SYSTEM_METRICS_FOR_DPI {
+    // We are on Windows 10 Anniversary Update (1607) or later.
+    GetSystemMetricsForDpi(index, dpi)
+  } else {
+    // The metrics are those of the system DPI.
+    let hdc = GetDC(HWND::default());
+    let system_dpi = GetDeviceCaps(hdc, LOGPIXELSX).max(1);
+    ReleaseDC(HWND::default(), hdc);
+    GetSystemMetrics(index) * dpi as i32 / system_dpi
+  }
+}
+
 pub unsafe fn hwnd_dpi(hwnd: HWND) -> u32 {
   let hdc = GetDC(hwnd);
   if hdc.is_invalid() {
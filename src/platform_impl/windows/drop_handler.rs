@@ -9,10 +9,10 @@ use windows::{
     Foundation::{self as win32f, HWND, POINTL, PWSTR},
     System::{
       Com::{IDataObject, DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL},
-      Ole::{DROPEFFECT_COPY, DROPEFFECT_NONE},
+      Ole::{ReleaseStgMedium, DROPEFFECT_COPY, DROPEFFECT_NONE},
       SystemServices::CF_HDROP,
     },
-    UI::Shell::{DragFinish, DragQueryFileW, HDROP},
+    UI::Shell::{DragQueryFileW, HDROP},
   },
 };
 
@@ -49,13 +49,12 @@ impl FileDropHandler {
     pdwEffect: *mut u32,
   ) -> windows::core::Result<()> {
     use crate::event::WindowEvent::HoveredFile;
-    let hdrop = Self::iterate_filenames(pDataObj, |filename| {
+    self.hovered_is_valid = Self::iterate_filenames(pDataObj, |filename| {
       (self.send_event)(Event::WindowEvent {
         window_id: SuperWindowId(WindowId(self.window.0)),
         event: HoveredFile(filename),
       });
     });
-    self.hovered_is_valid = hdrop.is_some();
     self.cursor_effect = if self.hovered_is_valid {
       DROPEFFECT_COPY
     } else {
@@ -91,22 +90,22 @@ impl FileDropHandler {
     pDataObj: &Option<IDataObject>,
     _grfKeyState: u32,
     _pt: POINTL,
-    _pdwEffect: *mut u32,
+    pdwEffect: *mut u32,
   ) -> windows::core::Result<()> {
     use crate::event::WindowEvent::DroppedFile;
-    let hdrop = Self::iterate_filenames(pDataObj, |filename| {
+    Self::iterate_filenames(pDataObj, |filename| {
       (self.send_event)(Event::WindowEvent {
         window_id: SuperWindowId(WindowId(self.window.0)),
         event: DroppedFile(filename),
       });
     });
-    if let Some(hdrop) = hdrop {
-      DragFinish(hdrop);
-    }
+    // tells the source whether the files were copied
+    *pdwEffect = self.cursor_effect;
     Ok(())
   }
 
-  unsafe fn iterate_filenames<F>(data_obj: &Option<IDataObject>, callback: F) -> Option<HDROP>
+  /// Calls `callback` with the path of each file of `data_obj`, returns whether it holds files.
+  unsafe fn iterate_filenames<F>(data_obj: &Option<IDataObject>, callback: F) -> bool
   where
     F: Fn(PathBuf),
   {
@@ -123,7 +122,7 @@ impl FileDropHandler {
       .expect("Received null IDataObject")
       .GetData(&drop_format)
     {
-      Ok(medium) => {
+      Ok(mut medium) => {
         let hglobal = medium.Anonymous.hGlobal;
         let hdrop = HDROP(hglobal);
 
@@ -145,7 +144,9 @@ impl FileDropHandler {
           callback(OsString::from_wide(&path_buf[0..character_count]).into());
         }
 
-        Some(hdrop)
+        // the medium belongs to us, `DragFinish` is only for the `HDROP` of `WM_DROPFILES`
+        ReleaseStgMedium(&mut medium);
+        true
       }
       Err(error) => {
         debug!(
@@ -159,7 +160,7 @@ impl FileDropHandler {
             _ => "Unexpected error occured while processing dropped/hovered item.",
           }
         );
-        None
+        false
       }
     }
   }
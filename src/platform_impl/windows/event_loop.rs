@@ -8,7 +8,7 @@ mod runner;
 use crossbeam_channel::{self as channel, Receiver, Sender};
 use parking_lot::Mutex;
 use std::{
-  cell::Cell,
+  cell::{Cell, RefCell},
   collections::VecDeque,
   marker::PhantomData,
   mem, panic, ptr,
@@ -20,18 +20,19 @@ use std::{
 use windows::Win32::{
   Devices::HumanInterfaceDevice::*,
   Foundation::{
-    BOOL, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, POINT, PWSTR, RECT, WAIT_TIMEOUT, WPARAM,
+    CloseHandle, BOOL, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, POINT, PWSTR, RECT, WAIT_TIMEOUT,
+    WPARAM,
   },
   Graphics::Gdi::*,
   System::{
     LibraryLoader::GetModuleHandleW,
     Ole::{IDropTarget, RevokeDragDrop},
-    Threading::GetCurrentThreadId,
+    Threading::{CreateWaitableTimerExW, GetCurrentThreadId, SetWaitableTimer},
     WindowsProgramming::INFINITE,
   },
   UI::{
     Controls::{self as win32c, HOVER_DEFAULT},
-    Input::{KeyboardAndMouse::*, Pointer::*, Touch::*, *},
+    Input::{Ime::*, KeyboardAndMouse::*, Pointer::*, Touch::*, *},
     Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
     WindowsAndMessaging::{self as win32wm, *},
   },
@@ -40,19 +41,22 @@ use windows::Win32::{
 use crate::{
   accelerator::AcceleratorId,
   dpi::{PhysicalPosition, PhysicalSize},
-  event::{DeviceEvent, Event, Force, RawKeyEvent, Touch, TouchPhase, WindowEvent},
+  event::{DeviceEvent, Event, Force, Ime, RawKeyEvent, Touch, TouchPhase, WindowEvent},
   event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
   keyboard::{KeyCode, ModifiersState},
+  menu::{MenuError, MenuId, MenuType},
   monitor::MonitorHandle as RootMonitorHandle,
   platform_impl::platform::{
     accelerator,
-    dark_mode::try_theme,
+    dark_mode::{refresh_title_bar, try_theme},
     dpi::{become_dpi_aware, dpi_to_scale_factor, enable_non_client_dpi_scaling},
     keyboard::is_msg_keyboard_related,
     keyboard_layout::LAYOUT_CACHE,
-    minimal_ime::is_msg_ime_related,
+    menu::{self, Menu},
+    minimal_ime::{self, is_msg_ime_related},
     monitor::{self, MonitorHandle},
     raw_input, util,
+    window::set_transparent_background,
     window_state::{CursorFlags, WindowFlags, WindowState},
     wrap_device_id, WindowId, DEVICE_ID,
   },
@@ -100,6 +104,8 @@ pub(crate) struct SubclassInput<T: 'static> {
   pub _file_drop_handler: Option<IDropTarget>,
   pub subclass_removed: Cell<bool>,
   pub recurse_depth: Cell<u32>,
+  /// Sends the menu events of the accelerators set with `WindowExtWindows::set_accelerators`.
+  pub accelerator_handler: menu::MenuHandler,
 }
 
 impl<T> SubclassInput<T> {
@@ -111,6 +117,24 @@ impl<T> SubclassInput<T> {
 struct ThreadMsgTargetSubclassInput<T: 'static> {
   event_loop_runner: EventLoopRunnerShared<T>,
   user_event_receiver: Receiver<T>,
+  /// The last position reported by a mouse moving in absolute coordinates, in pixels.
+  last_absolute_mouse_position: Cell<Option<(i32, i32)>>,
+  /// The monitors as of the last `DisplaysChanged` event, to only emit it for actual changes.
+  displays: RefCell<Vec<DisplayConfiguration>>,
+}
+
+type DisplayConfiguration = (MonitorHandle, PhysicalPosition<i32>, PhysicalSize<u32>, f64);
+
+fn display_configuration() -> Vec<DisplayConfiguration> {
+  monitor::available_monitors()
+    .into_iter()
+    .map(|monitor| {
+      let position = monitor.position();
+      let size = monitor.size();
+      let scale_factor = monitor.scale_factor();
+      (monitor, position, size, scale_factor)
+    })
+    .collect()
 }
 
 impl<T> ThreadMsgTargetSubclassInput<T> {
@@ -233,8 +257,11 @@ impl<T: 'static> EventLoop<T> {
 
       runner.poll();
       'main: loop {
-        if !GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
-          break 'main 0;
+        match GetMessageW(&mut msg, HWND::default(), 0, 0).0 {
+          // `WM_QUIT`, e.g. from `PostQuitMessage`, carries the exit code
+          0 => break 'main msg.wParam.0 as i32,
+          -1 => break 'main 1,
+          _ => {}
         }
 
         // global accelerator
@@ -246,10 +273,10 @@ impl<T: 'static> EventLoop<T> {
         }
 
         // window accelerator
-        let accels = accelerator::find_accels(GetAncestor(msg.hwnd, GA_ROOT));
-        let translated = accels.map_or(false, |it| {
-          TranslateAcceleratorW(msg.hwnd, it.handle(), &msg) != 0
-        });
+        let accels = accelerator::find_accels(msg.hwnd);
+        let translated = accels
+          .iter()
+          .any(|(hwnd, it)| accelerator::translate_accelerator(*hwnd, &msg, it));
         if !translated {
           TranslateMessage(&msg);
           DispatchMessageW(&msg);
@@ -301,6 +328,13 @@ impl<T> EventLoopWindowTarget<T> {
     let monitor = monitor::primary_monitor();
     Some(RootMonitorHandle { inner: monitor })
   }
+
+  pub fn attach_menu(&self, hwnd: HWND, menu: Menu) -> Result<(), MenuError> {
+    // report the events for the top-level window the control belongs to
+    let window_id = RootWindowId(WindowId(unsafe { GetAncestor(hwnd, GA_ROOT) }.0));
+    let menu_handler = menu::MenuHandler::new(self, MenuType::MenuBar, Some(window_id));
+    menu::attach(menu, hwnd, menu_handler).map(|_| ())
+  }
 }
 
 fn main_thread_id() -> u32 {
@@ -348,6 +382,18 @@ fn wait_thread(parent_thread_id: u32, msg_window_id: HWND) {
       LPARAM(cur_thread_id as _),
     );
 
+    // The timeout of `MsgWaitForMultipleObjectsEx` follows the resolution of the system timer,
+    // 15.6ms by default, while a high resolution timer wakes the thread much closer to the
+    // requested instant. Those timers are only available from Windows 10 1803.
+    const CREATE_WAITABLE_TIMER_HIGH_RESOLUTION: u32 = 0x2;
+    const TIMER_ALL_ACCESS: u32 = 0x1F_0003;
+    let timer = CreateWaitableTimerExW(
+      ptr::null(),
+      PWSTR::default(),
+      CREATE_WAITABLE_TIMER_HIGH_RESOLUTION,
+      TIMER_ALL_ACCESS,
+    );
+
     let mut wait_until_opt = None;
     'main: loop {
       // Zeroing out the message ensures that the `WaitUntilInstantBox` doesn't get
@@ -376,17 +422,29 @@ fn wait_thread(parent_thread_id: u32, msg_window_id: HWND) {
       if let Some(wait_until) = wait_until_opt {
         let now = Instant::now();
         if now < wait_until {
-          // MsgWaitForMultipleObjects tends to overshoot just a little bit. We subtract
-          // 1 millisecond from the requested time and spinlock for the remainder to
-          // compensate for that.
-          let resume_reason = MsgWaitForMultipleObjectsEx(
-            0,
-            ptr::null(),
-            dur2timeout(wait_until - now).saturating_sub(1),
-            QS_ALLEVENTS,
-            MWMO_INPUTAVAILABLE,
-          );
-          if resume_reason == WAIT_TIMEOUT {
+          let time_reached = if !timer.is_invalid() {
+            // Negative due times are relative, in 100ns intervals. The timer is set again after
+            // every message, so an early wake up doesn't lose it.
+            let due_time = -(((wait_until - now).as_nanos() / 100) as i64).max(1);
+            SetWaitableTimer(timer, &due_time, 0, None, ptr::null(), BOOL::from(false));
+            let resume_reason =
+              MsgWaitForMultipleObjectsEx(1, &timer, INFINITE, QS_ALLEVENTS, MWMO_INPUTAVAILABLE);
+            // `WAIT_OBJECT_0`, the timer is signaled
+            resume_reason == 0
+          } else {
+            // MsgWaitForMultipleObjects tends to overshoot just a little bit. We subtract
+            // 1 millisecond from the requested time and spinlock for the remainder to
+            // compensate for that.
+            let resume_reason = MsgWaitForMultipleObjectsEx(
+              0,
+              ptr::null(),
+              dur2timeout(wait_until - now).saturating_sub(1),
+              QS_ALLEVENTS,
+              MWMO_INPUTAVAILABLE,
+            );
+            resume_reason == WAIT_TIMEOUT
+          };
+          if time_reached {
             PostMessageW(
               msg_window_id,
               *PROCESS_NEW_EVENTS_MSG_ID,
@@ -406,6 +464,10 @@ fn wait_thread(parent_thread_id: u32, msg_window_id: HWND) {
         }
       }
     }
+
+    if !timer.is_invalid() {
+      CloseHandle(timer);
+    }
   }
 }
 
@@ -524,22 +586,24 @@ impl<T: 'static> Clone for EventLoopProxy<T> {
 
 impl<T: 'static> EventLoopProxy<T> {
   pub fn send_event(&self, event: T) -> Result<(), EventLoopClosed<T>> {
+    // The event is queued before waking the loop up, so the wake up message can't be handled
+    // before the event is there. The receiver is dropped along with the target window.
+    self
+      .event_send
+      .send(event)
+      .map_err(|error| EventLoopClosed(error.0))?;
     unsafe {
-      if PostMessageW(self.target_window, *USER_EVENT_MSG_ID, WPARAM(0), LPARAM(0)).as_bool() {
-        self.event_send.send(event).ok();
-        Ok(())
-      } else {
-        Err(EventLoopClosed(event))
-      }
+      PostMessageW(self.target_window, *USER_EVENT_MSG_ID, WPARAM(0), LPARAM(0));
     }
+    Ok(())
   }
 }
 
 type WaitUntilInstantBox = Box<Instant>;
 
 lazy_static! {
-    // Message sent by the `EventLoopProxy` when we want to wake up the thread.
-    // WPARAM and LPARAM are unused.
+    // Message sent by the `EventLoopProxy` when we want to wake up the thread, after queuing
+    // the user event. WPARAM and LPARAM are unused.
     static ref USER_EVENT_MSG_ID: u32 = {
         unsafe {
             RegisterWindowMessageA("Tao::WakeupMsg")
@@ -583,6 +647,13 @@ lazy_static! {
             RegisterWindowMessageA("Tao::DestroyMsg")
         }
     };
+    // Message sent by a `Window` when an item of a menu shown with `show_context_menu` was picked.
+    // WPARAM is the id of the item, and LPARAM is unused.
+    pub static ref CONTEXT_MENU_MSG_ID: u32 = {
+        unsafe {
+            RegisterWindowMessageA("Tao::ContextMenuMsg")
+        }
+    };
     // WPARAM is a bool specifying the `WindowFlags::MARKER_RETAIN_STATE_ON_SIZE` flag. See the
     // documentation in the `window_state` module for more information.
     pub static ref SET_RETAIN_STATE_ON_SIZE_MSG_ID: u32 = unsafe {
@@ -650,6 +721,8 @@ fn subclass_event_target_window<T>(
     let subclass_input = ThreadMsgTargetSubclassInput {
       event_loop_runner,
       user_event_receiver: rx,
+      last_absolute_mouse_position: Cell::new(None),
+      displays: RefCell::new(display_configuration()),
     };
     let input_ptr = Box::into_raw(Box::new(subclass_input));
     let subclass_result = SetWindowSubclass(
@@ -695,6 +768,12 @@ unsafe fn release_mouse(mut window_state: parking_lot::MutexGuard<'_, WindowStat
 
 const WINDOW_SUBCLASS_ID: usize = 0;
 const THREAD_EVENT_TARGET_SUBCLASS_ID: usize = 1;
+
+/// The timer of the thread event target gathering consecutive display changes.
+const DISPLAYS_CHANGED_TIMER_ID: usize = 1;
+/// How long the displays have to stay unchanged before `DisplaysChanged` is emitted, in
+/// milliseconds.
+const DISPLAYS_CHANGED_DELAY: u32 = 250;
 pub(crate) fn subclass_window<T>(window: HWND, subclass_input: SubclassInput<T>) {
   subclass_input.event_loop_runner.register_window(window);
   let input_ptr = Box::into_raw(Box::new(subclass_input));
@@ -829,7 +908,6 @@ fn update_modifiers<T>(window: HWND, subclass_input: &SubclassInput<T>) -> Modif
 // This is the callback that is called by `DispatchMessage` in the events loop.
 //
 // Returning 0 tells the Win32 API that the message has been processed.
-// FIXME: detect WM_DWMCOMPOSITIONCHANGED and call DwmEnableBlurBehindWindow if necessary
 unsafe extern "system" fn public_window_callback<T: 'static>(
   window: HWND,
   msg: u32,
@@ -999,6 +1077,7 @@ unsafe fn public_window_callback_inner<T: 'static>(
     }
 
     win32wm::WM_NCDESTROY => {
+      accelerator::register_window_accel(window, &[]);
       remove_window_subclass::<T>(window);
       subclass_input.subclass_removed.set(true);
       result = ProcResult::Value(LRESULT(0));
@@ -1098,6 +1177,17 @@ unsafe fn public_window_callback_inner<T: 'static>(
       result = ProcResult::Value(LRESULT(0));
     }
 
+    // `HIWORD` is 1 when the command comes from an accelerator
+    win32wm::WM_COMMAND
+      if util::HIWORD(wparam.0 as u32) == 1
+        && accelerator::is_window_accel(window, util::LOWORD(wparam.0 as u32)) =>
+    {
+      subclass_input
+        .accelerator_handler
+        .send_menu_event(util::LOWORD(wparam.0 as u32));
+      result = ProcResult::Value(LRESULT(0));
+    }
+
     // this is necessary for us to maintain minimize/restore state
     win32wm::WM_SYSCOMMAND => {
       if wparam.0 == SC_RESTORE as _ {
@@ -1596,10 +1686,58 @@ unsafe fn public_window_callback_inner<T: 'static>(
       result = ProcResult::Value(LRESULT(0));
     }
 
+    // The composition is left to the default handling, which draws it and sends the accepted
+    // text as characters, the messages are only observed to report it
+    win32wm::WM_IME_STARTCOMPOSITION => {
+      subclass_input.send_event(Event::WindowEvent {
+        window_id: RootWindowId(WindowId(window.0)),
+        event: WindowEvent::Ime(Ime::Enabled),
+      });
+    }
+
+    win32wm::WM_IME_COMPOSITION => {
+      let flags = lparam.0 as u32;
+      if util::has_flag(flags, GCS_RESULTSTR) {
+        if let Some(text) = minimal_ime::composition_result(window) {
+          subclass_input.send_event(Event::WindowEvent {
+            window_id: RootWindowId(WindowId(window.0)),
+            event: WindowEvent::Ime(Ime::Preedit(String::new(), None)),
+          });
+          subclass_input.send_event(Event::WindowEvent {
+            window_id: RootWindowId(WindowId(window.0)),
+            event: WindowEvent::Ime(Ime::Commit(text)),
+          });
+        }
+      }
+      if util::has_flag(flags, GCS_COMPSTR) {
+        if let Some((text, range)) = minimal_ime::composition_preedit(window) {
+          subclass_input.send_event(Event::WindowEvent {
+            window_id: RootWindowId(WindowId(window.0)),
+            event: WindowEvent::Ime(Ime::Preedit(text, range)),
+          });
+        }
+      }
+    }
+
+    win32wm::WM_IME_ENDCOMPOSITION => {
+      subclass_input.send_event(Event::WindowEvent {
+        window_id: RootWindowId(WindowId(window.0)),
+        event: WindowEvent::Ime(Ime::Disabled),
+      });
+    }
+
     win32wm::WM_SETFOCUS => {
       use crate::event::WindowEvent::Focused;
       update_modifiers(window, subclass_input);
 
+      // apply the cursor grab released when losing focus again
+      subclass_input
+        .window_state
+        .lock()
+        .mouse
+        .set_cursor_flags(window, |_| ())
+        .ok();
+
       subclass_input.send_event(Event::WindowEvent {
         window_id: RootWindowId(WindowId(window.0)),
         event: Focused(true),
@@ -1611,6 +1749,12 @@ unsafe fn public_window_callback_inner<T: 'static>(
     win32wm::WM_KILLFOCUS => {
       use crate::event::WindowEvent::{Focused, ModifiersChanged};
 
+      // the clip is global to the system, release it while other windows are used
+      let cursor_flags = subclass_input.window_state.lock().mouse.cursor_flags();
+      if cursor_flags.intersects(CursorFlags::GRABBED | CursorFlags::LOCKED) {
+        util::set_cursor_clip(None).ok();
+      }
+
       subclass_input.window_state.lock().modifiers_state = ModifiersState::empty();
       subclass_input.send_event(Event::WindowEvent {
         window_id: RootWindowId(WindowId(window.0)),
@@ -1675,6 +1819,67 @@ unsafe fn public_window_callback_inner<T: 'static>(
       result = ProcResult::Value(LRESULT(0));
     }
 
+    win32wm::WM_SIZING => {
+      let (aspect_ratio, min_size, max_size, scale_factor) = {
+        let window_state = subclass_input.window_state.lock();
+        (
+          window_state.aspect_ratio,
+          window_state.min_size,
+          window_state.max_size,
+          window_state.scale_factor,
+        )
+      };
+
+      if let Some((ratio_width, ratio_height)) =
+        aspect_ratio.filter(|(width, height)| *width > 0 && *height > 0)
+      {
+        let ratio = ratio_width as f64 / ratio_height as f64;
+        let rect = &mut *(lparam.0 as *mut RECT);
+        let edge = wparam.0 as u32;
+
+        // the ratio applies to the inner size, without the decorations
+        let mut window_rect = RECT::default();
+        let mut client_rect = RECT::default();
+        GetWindowRect(window, &mut window_rect);
+        GetClientRect(window, &mut client_rect);
+        let margin_width = (window_rect.right - window_rect.left) - client_rect.right;
+        let margin_height = (window_rect.bottom - window_rect.top) - client_rect.bottom;
+
+        // dragging the top or bottom edge drives the width, the other edges drive the height
+        let mut width = match edge {
+          WMSZ_TOP | WMSZ_BOTTOM => (rect.bottom - rect.top - margin_height) as f64 * ratio,
+          _ => (rect.right - rect.left - margin_width) as f64,
+        };
+
+        // keep the size bounds, unless they can't be met with this ratio
+        let mut lower = 0.0f64;
+        let mut upper = f64::INFINITY;
+        if let Some(min_size) = min_size {
+          let min_size: PhysicalSize<f64> = min_size.to_physical(scale_factor);
+          lower = min_size.width.max(min_size.height * ratio);
+        }
+        if let Some(max_size) = max_size {
+          let max_size: PhysicalSize<f64> = max_size.to_physical(scale_factor);
+          upper = max_size.width.min(max_size.height * ratio);
+        }
+        if lower <= upper {
+          width = width.clamp(lower, upper);
+        }
+
+        let outer_width = width.round() as i32 + margin_width;
+        let outer_height = (width / ratio).round() as i32 + margin_height;
+        match edge {
+          WMSZ_LEFT | WMSZ_TOPLEFT | WMSZ_BOTTOMLEFT => rect.left = rect.right - outer_width,
+          _ => rect.right = rect.left + outer_width,
+        }
+        match edge {
+          WMSZ_TOP | WMSZ_TOPLEFT | WMSZ_TOPRIGHT => rect.top = rect.bottom - outer_height,
+          _ => rect.bottom = rect.top + outer_height,
+        }
+        result = ProcResult::Value(LRESULT(1));
+      }
+    }
+
     // Only sent on Windows 8.1 or newer. On Windows 7 and older user has to log out to change
     // DPI, therefore all applications are closed while DPI is changing.
     win32wm::WM_DPICHANGED => {
@@ -1896,6 +2101,7 @@ unsafe fn public_window_callback_inner<T: 'static>(
         if window_state.current_theme != new_theme {
           window_state.current_theme = new_theme;
           mem::drop(window_state);
+          refresh_title_bar(window);
           subclass_input.send_event(Event::WindowEvent {
             window_id: RootWindowId(WindowId(window.0)),
             event: ThemeChanged(new_theme),
@@ -1904,6 +2110,17 @@ unsafe fn public_window_callback_inner<T: 'static>(
       }
     }
 
+    // Windows 7 can turn composition off and on, which drops the transparency of the window
+    win32wm::WM_DWMCOMPOSITIONCHANGED => {
+      let win_flags = subclass_input.window_state.lock().window_flags();
+      if win_flags.contains(WindowFlags::TRANSPARENT)
+        && !win_flags.contains(WindowFlags::NO_BACK_BUFFER)
+      {
+        set_transparent_background(window);
+      }
+      result = ProcResult::Value(LRESULT(0));
+    }
+
     win32wm::WM_NCCALCSIZE => {
       let win_flags = subclass_input.window_state.lock().window_flags();
 
@@ -1945,6 +2162,14 @@ unsafe fn public_window_callback_inner<T: 'static>(
       if msg == *DESTROY_MSG_ID {
         DestroyWindow(window);
         result = ProcResult::Value(LRESULT(0));
+      } else if msg == *CONTEXT_MENU_MSG_ID {
+        subclass_input.send_event(Event::MenuEvent {
+          window_id: Some(RootWindowId(WindowId(window.0))),
+          menu_id: MenuId(wparam.0 as u16),
+          origin: MenuType::ContextMenu,
+          modifiers: LAYOUT_CACHE.lock().unwrap().get_agnostic_mods(),
+        });
+        result = ProcResult::Value(LRESULT(0));
       } else if msg == *SET_RETAIN_STATE_ON_SIZE_MSG_ID {
         let mut window_state = subclass_input.window_state.lock();
         window_state.set_window_flags_in_place(|f| {
@@ -2043,8 +2268,44 @@ unsafe extern "system" fn thread_event_target_callback<T: 'static>(
       DefSubclassProc(window, msg, wparam, lparam)
     }
 
+    // Both are broadcast to the top-level windows, which this window is. Plugging in a monitor
+    // sends several of them, so (re)start a timer and only look at the monitors once it fires.
+    win32wm::WM_DISPLAYCHANGE => {
+      SetTimer(
+        window,
+        DISPLAYS_CHANGED_TIMER_ID,
+        DISPLAYS_CHANGED_DELAY,
+        None,
+      );
+      DefSubclassProc(window, msg, wparam, lparam)
+    }
+    win32wm::WM_DEVICECHANGE if wparam.0 as u32 == DBT_DEVNODES_CHANGED => {
+      SetTimer(
+        window,
+        DISPLAYS_CHANGED_TIMER_ID,
+        DISPLAYS_CHANGED_DELAY,
+        None,
+      );
+      DefSubclassProc(window, msg, wparam, lparam)
+    }
+    win32wm::WM_TIMER if wparam.0 == DISPLAYS_CHANGED_TIMER_ID => {
+      KillTimer(window, DISPLAYS_CHANGED_TIMER_ID);
+
+      // `WM_DEVICECHANGE` is also sent for devices which aren't monitors.
+      let displays = display_configuration();
+      let changed = *subclass_input.displays.borrow() != displays;
+      if changed {
+        *subclass_input.displays.borrow_mut() = displays;
+        subclass_input.send_event(Event::DisplaysChanged);
+      }
+
+      LRESULT(0)
+    }
+
     _ if msg == *USER_EVENT_MSG_ID => {
-      if let Ok(event) = subclass_input.user_event_receiver.recv() {
+      // Drain the queue, in case a wake up message was lost, e.g. when the message queue of the
+      // thread was full. The messages of the events handled here then find it empty.
+      while let Ok(event) = subclass_input.user_event_receiver.try_recv() {
         subclass_input.send_event(Event::UserEvent(event));
       }
       LRESULT(0)
@@ -2118,10 +2379,41 @@ unsafe fn handle_raw_input<T: 'static>(
   if data.header.dwType == RIM_TYPEMOUSE {
     let mouse = data.data.mouse;
 
-    if util::has_flag(mouse.usFlags, MOUSE_MOVE_RELATIVE as u16) {
-      let x = mouse.lLastX as f64;
-      let y = mouse.lLastY as f64;
+    // `MOUSE_MOVE_RELATIVE` is 0, the movement is relative whenever it isn't absolute
+    let motion = if util::has_flag(mouse.usFlags, MOUSE_MOVE_ABSOLUTE as u16) {
+      // Tablets, touch screens and remote desktop sessions report positions normalized to
+      // `0..=65535` instead, turn them into deltas in pixels
+      let (left, top, width, height) =
+        if util::has_flag(mouse.usFlags, MOUSE_VIRTUAL_DESKTOP as u16) {
+          (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+          )
+        } else {
+          (
+            0,
+            0,
+            GetSystemMetrics(SM_CXSCREEN),
+            GetSystemMetrics(SM_CYSCREEN),
+          )
+        };
+      let position = (
+        left + (mouse.lLastX as i64 * width as i64 / 65535) as i32,
+        top + (mouse.lLastY as i64 * height as i64 / 65535) as i32,
+      );
+      subclass_input
+        .last_absolute_mouse_position
+        .replace(Some(position))
+        .map(|last| ((position.0 - last.0) as f64, (position.1 - last.1) as f64))
+    } else {
+      // another device moved the cursor in between, the next absolute packet starts over
+      subclass_input.last_absolute_mouse_position.set(None);
+      Some((mouse.lLastX as f64, mouse.lLastY as f64))
+    };
 
+    if let Some((x, y)) = motion {
       if x != 0.0 {
         subclass_input.send_event(Event::DeviceEvent {
           device_id,
@@ -2149,7 +2441,7 @@ unsafe fn handle_raw_input<T: 'static>(
       RI_MOUSE_WHEEL as u16,
     ) {
       // We must cast to SHORT first, becaues `usButtonData` must be interpreted as signed.
-      let delta = mouse.Anonymous.Anonymous.usButtonData as f32 / WHEEL_DELTA as f32;
+      let delta = mouse.Anonymous.Anonymous.usButtonData as i16 as f32 / WHEEL_DELTA as f32;
       subclass_input.send_event(Event::DeviceEvent {
         device_id,
         event: MouseWheel {
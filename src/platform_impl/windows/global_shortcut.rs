@@ -5,7 +5,11 @@ use crate::{
   global_shortcut::{GlobalShortcut as RootGlobalShortcut, ShortcutManagerError},
   keyboard::ModifiersState,
 };
-use windows::Win32::{Foundation::HWND, UI::Input::KeyboardAndMouse::*};
+use std::io;
+use windows::Win32::{
+  Foundation::{ERROR_HOTKEY_ALREADY_REGISTERED, HWND},
+  UI::Input::KeyboardAndMouse::*,
+};
 
 #[derive(Debug, Clone)]
 pub struct ShortcutManager {
@@ -49,9 +53,17 @@ impl ShortcutManager {
             u32::from(vk_code),
           );
           if !result.as_bool() {
-            return Err(ShortcutManagerError::InvalidAccelerator(
-              "Unable to register accelerator with `RegisterHotKey`.".into(),
-            ));
+            let error = io::Error::last_os_error();
+            // the hotkey is already used by another application
+            if error.raw_os_error() == Some(ERROR_HOTKEY_ALREADY_REGISTERED as i32) {
+              return Err(ShortcutManagerError::AcceleratorAlreadyRegistered(
+                accelerator,
+              ));
+            }
+            return Err(ShortcutManagerError::InvalidAccelerator(format!(
+              "Unable to register accelerator with `RegisterHotKey`: {}",
+              error
+            )));
           }
           let shortcut = GlobalShortcut { accelerator };
           self.shortcuts.push(shortcut.clone());
@@ -75,7 +87,11 @@ impl ShortcutManager {
     &self,
     shortcut: RootGlobalShortcut,
   ) -> Result<(), ShortcutManagerError> {
-    shortcut.0.unregister();
+    if !shortcut.0.unregister() {
+      return Err(ShortcutManagerError::AcceleratorNotRegistered(
+        shortcut.0.accelerator,
+      ));
+    }
     Ok(())
   }
 }
@@ -89,9 +105,8 @@ impl GlobalShortcut {
   pub fn id(&self) -> AcceleratorId {
     self.accelerator.clone().id()
   }
-  pub(crate) fn unregister(&self) {
-    unsafe {
-      UnregisterHotKey(HWND::default(), self.accelerator.clone().id().0 as i32);
-    }
+  // Returns `false` if the hotkey wasn't registered.
+  pub(crate) fn unregister(&self) -> bool {
+    unsafe { UnregisterHotKey(HWND::default(), self.accelerator.clone().id().0 as i32).as_bool() }
   }
 }
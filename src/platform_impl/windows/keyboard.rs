@@ -455,6 +455,7 @@ impl KeyEventBuilder {
       key_state,
       scancode,
       is_repeat: false,
+      repeat_count: 1,
       code,
       location: get_location(scancode, locale_id),
       utf16parts: Vec::with_capacity(8),
@@ -493,6 +494,7 @@ struct PartialKeyEventInfo {
   scancode: ExScancode,
   key_state: ElementState,
   is_repeat: bool,
+  repeat_count: u16,
   code: KeyCode,
   location: KeyLocation,
   logical_key: PartialLogicalKey,
@@ -596,6 +598,7 @@ impl PartialKeyEventInfo {
       logical_key,
       key_without_modifiers,
       is_repeat: lparam_struct.is_repeat,
+      repeat_count: lparam_struct.repeat_count,
       code,
       location,
       utf16parts: Vec::with_capacity(8),
@@ -654,6 +657,8 @@ impl PartialKeyEventInfo {
       platform_specific: KeyEventExtra {
         text_with_all_modifiers: char_with_all_modifiers,
         key_without_modifiers: self.key_without_modifiers,
+        repeat_count: self.repeat_count,
+        scancode: u32::from(self.scancode),
       },
     }
   }
@@ -666,6 +671,10 @@ struct KeyLParam {
 
   /// This is `previous_state XOR transition_state`. See the lParam for WM_KEYDOWN and WM_KEYUP for further details.
   pub is_repeat: bool,
+
+  /// The number of times the keystroke is repeated, when the messages come faster than they are
+  /// handled.
+  pub repeat_count: u16,
 }
 
 fn destructure_key_lparam(lparam: LPARAM) -> KeyLParam {
@@ -675,6 +684,7 @@ fn destructure_key_lparam(lparam: LPARAM) -> KeyLParam {
     scancode: ((lparam.0 >> 16) & 0xFF) as u8,
     extended: ((lparam.0 >> 24) & 0x01) != 0,
     is_repeat: (previous_state ^ transition_state) != 0,
+    repeat_count: (lparam.0 & 0xFFFF) as u16,
   }
 }
 
@@ -895,6 +905,21 @@ pub(crate) fn key_to_vk(key: &KeyCode) -> Option<VIRTUAL_KEY> {
     KeyCode::F22 => VK_F22,
     KeyCode::F23 => VK_F23,
     KeyCode::F24 => VK_F24,
+    KeyCode::Numpad0 => VK_NUMPAD0,
+    KeyCode::Numpad1 => VK_NUMPAD1,
+    KeyCode::Numpad2 => VK_NUMPAD2,
+    KeyCode::Numpad3 => VK_NUMPAD3,
+    KeyCode::Numpad4 => VK_NUMPAD4,
+    KeyCode::Numpad5 => VK_NUMPAD5,
+    KeyCode::Numpad6 => VK_NUMPAD6,
+    KeyCode::Numpad7 => VK_NUMPAD7,
+    KeyCode::Numpad8 => VK_NUMPAD8,
+    KeyCode::Numpad9 => VK_NUMPAD9,
+    KeyCode::NumpadAdd => VK_ADD,
+    KeyCode::NumpadSubtract => VK_SUBTRACT,
+    KeyCode::NumpadMultiply => VK_MULTIPLY,
+    KeyCode::NumpadDivide => VK_DIVIDE,
+    KeyCode::NumpadDecimal => VK_DECIMAL,
     KeyCode::NumLock => VK_NUMLOCK,
     KeyCode::ScrollLock => VK_SCROLL,
     KeyCode::BrowserBack => VK_BROWSER_BACK,
@@ -916,3 +941,259 @@ pub(crate) fn key_to_vk(key: &KeyCode) -> Option<VIRTUAL_KEY> {
     _ => return None,
   })
 }
+
+/// Converts a Windows virtual-key code into a [`KeyCode`], e.g. to handle the keys reported by a
+/// low-level keyboard hook.
+///
+/// `extended` is the extended-key flag of the key message (bit 24 of its `lParam`), which tells
+/// the right-hand <kbd>Ctrl</kbd> and <kbd>Alt</kbd> keys and the numpad <kbd>Enter</kbd> key
+/// apart. Returns `None` for the virtual keys tao doesn't map.
+pub fn vk_to_key(vk: i32, extended: bool) -> Option<KeyCode> {
+  let vk = (vk & 0xff) as VIRTUAL_KEY;
+  Some(match vk {
+    // letters and digits keep their virtual key on every layout
+    0x30..=0x39 => [
+      KeyCode::Digit0,
+      KeyCode::Digit1,
+      KeyCode::Digit2,
+      KeyCode::Digit3,
+      KeyCode::Digit4,
+      KeyCode::Digit5,
+      KeyCode::Digit6,
+      KeyCode::Digit7,
+      KeyCode::Digit8,
+      KeyCode::Digit9,
+    ][(vk - 0x30) as usize]
+      .clone(),
+    0x41..=0x5a => [
+      KeyCode::KeyA,
+      KeyCode::KeyB,
+      KeyCode::KeyC,
+      KeyCode::KeyD,
+      KeyCode::KeyE,
+      KeyCode::KeyF,
+      KeyCode::KeyG,
+      KeyCode::KeyH,
+      KeyCode::KeyI,
+      KeyCode::KeyJ,
+      KeyCode::KeyK,
+      KeyCode::KeyL,
+      KeyCode::KeyM,
+      KeyCode::KeyN,
+      KeyCode::KeyO,
+      KeyCode::KeyP,
+      KeyCode::KeyQ,
+      KeyCode::KeyR,
+      KeyCode::KeyS,
+      KeyCode::KeyT,
+      KeyCode::KeyU,
+      KeyCode::KeyV,
+      KeyCode::KeyW,
+      KeyCode::KeyX,
+      KeyCode::KeyY,
+      KeyCode::KeyZ,
+    ][(vk - 0x41) as usize]
+      .clone(),
+    win32km::VK_OEM_COMMA => KeyCode::Comma,
+    win32km::VK_OEM_MINUS => KeyCode::Minus,
+    win32km::VK_OEM_PERIOD => KeyCode::Period,
+    win32km::VK_SHIFT | win32km::VK_LSHIFT => KeyCode::ShiftLeft,
+    win32km::VK_RSHIFT => KeyCode::ShiftRight,
+    win32km::VK_CONTROL if extended => KeyCode::ControlRight,
+    win32km::VK_CONTROL | win32km::VK_LCONTROL => KeyCode::ControlLeft,
+    win32km::VK_RCONTROL => KeyCode::ControlRight,
+    win32km::VK_MENU if extended => KeyCode::AltRight,
+    win32km::VK_MENU | win32km::VK_LMENU => KeyCode::AltLeft,
+    win32km::VK_RMENU => KeyCode::AltRight,
+    win32km::VK_LWIN => KeyCode::SuperLeft,
+    win32km::VK_RWIN => KeyCode::SuperRight,
+    win32km::VK_BACK => KeyCode::Backspace,
+    win32km::VK_TAB => KeyCode::Tab,
+    win32km::VK_SPACE => KeyCode::Space,
+    win32km::VK_RETURN if extended => KeyCode::NumpadEnter,
+    win32km::VK_RETURN => KeyCode::Enter,
+    win32km::VK_PAUSE => KeyCode::Pause,
+    win32km::VK_CAPITAL => KeyCode::CapsLock,
+    win32km::VK_KANA => KeyCode::KanaMode,
+    win32km::VK_ESCAPE => KeyCode::Escape,
+    win32km::VK_NONCONVERT => KeyCode::NonConvert,
+    win32km::VK_PRIOR => KeyCode::PageUp,
+    win32km::VK_NEXT => KeyCode::PageDown,
+    win32km::VK_END => KeyCode::End,
+    win32km::VK_HOME => KeyCode::Home,
+    win32km::VK_LEFT => KeyCode::ArrowLeft,
+    win32km::VK_UP => KeyCode::ArrowUp,
+    win32km::VK_RIGHT => KeyCode::ArrowRight,
+    win32km::VK_DOWN => KeyCode::ArrowDown,
+    win32km::VK_SNAPSHOT => KeyCode::PrintScreen,
+    win32km::VK_INSERT => KeyCode::Insert,
+    win32km::VK_DELETE => KeyCode::Delete,
+    win32km::VK_HELP => KeyCode::Help,
+    win32km::VK_APPS => KeyCode::ContextMenu,
+    win32km::VK_F1 => KeyCode::F1,
+    win32km::VK_F2 => KeyCode::F2,
+    win32km::VK_F3 => KeyCode::F3,
+    win32km::VK_F4 => KeyCode::F4,
+    win32km::VK_F5 => KeyCode::F5,
+    win32km::VK_F6 => KeyCode::F6,
+    win32km::VK_F7 => KeyCode::F7,
+    win32km::VK_F8 => KeyCode::F8,
+    win32km::VK_F9 => KeyCode::F9,
+    win32km::VK_F10 => KeyCode::F10,
+    win32km::VK_F11 => KeyCode::F11,
+    win32km::VK_F12 => KeyCode::F12,
+    win32km::VK_F13 => KeyCode::F13,
+    win32km::VK_F14 => KeyCode::F14,
+    win32km::VK_F15 => KeyCode::F15,
+    win32km::VK_F16 => KeyCode::F16,
+    win32km::VK_F17 => KeyCode::F17,
+    win32km::VK_F18 => KeyCode::F18,
+    win32km::VK_F19 => KeyCode::F19,
+    win32km::VK_F20 => KeyCode::F20,
+    win32km::VK_F21 => KeyCode::F21,
+    win32km::VK_F22 => KeyCode::F22,
+    win32km::VK_F23 => KeyCode::F23,
+    win32km::VK_F24 => KeyCode::F24,
+    win32km::VK_NUMPAD0 => KeyCode::Numpad0,
+    win32km::VK_NUMPAD1 => KeyCode::Numpad1,
+    win32km::VK_NUMPAD2 => KeyCode::Numpad2,
+    win32km::VK_NUMPAD3 => KeyCode::Numpad3,
+    win32km::VK_NUMPAD4 => KeyCode::Numpad4,
+    win32km::VK_NUMPAD5 => KeyCode::Numpad5,
+    win32km::VK_NUMPAD6 => KeyCode::Numpad6,
+    win32km::VK_NUMPAD7 => KeyCode::Numpad7,
+    win32km::VK_NUMPAD8 => KeyCode::Numpad8,
+    win32km::VK_NUMPAD9 => KeyCode::Numpad9,
+    win32km::VK_ADD => KeyCode::NumpadAdd,
+    win32km::VK_SUBTRACT => KeyCode::NumpadSubtract,
+    win32km::VK_MULTIPLY => KeyCode::NumpadMultiply,
+    win32km::VK_DIVIDE => KeyCode::NumpadDivide,
+    win32km::VK_DECIMAL => KeyCode::NumpadDecimal,
+    win32km::VK_NUMLOCK => KeyCode::NumLock,
+    win32km::VK_SCROLL => KeyCode::ScrollLock,
+    win32km::VK_BROWSER_BACK => KeyCode::BrowserBack,
+    win32km::VK_BROWSER_FORWARD => KeyCode::BrowserForward,
+    win32km::VK_BROWSER_REFRESH => KeyCode::BrowserRefresh,
+    win32km::VK_BROWSER_STOP => KeyCode::BrowserStop,
+    win32km::VK_BROWSER_SEARCH => KeyCode::BrowserSearch,
+    win32km::VK_BROWSER_FAVORITES => KeyCode::BrowserFavorites,
+    win32km::VK_BROWSER_HOME => KeyCode::BrowserHome,
+    win32km::VK_VOLUME_MUTE => KeyCode::AudioVolumeMute,
+    win32km::VK_VOLUME_DOWN => KeyCode::AudioVolumeDown,
+    win32km::VK_VOLUME_UP => KeyCode::AudioVolumeUp,
+    win32km::VK_MEDIA_NEXT_TRACK => KeyCode::MediaTrackNext,
+    win32km::VK_MEDIA_PREV_TRACK => KeyCode::MediaTrackPrevious,
+    win32km::VK_MEDIA_STOP => KeyCode::MediaStop,
+    win32km::VK_MEDIA_PLAY_PAUSE => KeyCode::MediaPlayPause,
+    win32km::VK_LAUNCH_MAIL => KeyCode::LaunchMail,
+    win32km::VK_CONVERT => KeyCode::Convert,
+    // the remaining punctuation keys depend on the layout, like in `key_to_vk`
+    _ => match char::from_u32(unsafe { MapVirtualKeyW(vk as u32, MAPVK_VK_TO_CHAR) }) {
+      Some('=') => KeyCode::Equal,
+      Some(';') => KeyCode::Semicolon,
+      Some('/') => KeyCode::Slash,
+      Some('\\') => KeyCode::Backslash,
+      Some('\'') => KeyCode::Quote,
+      Some('`') => KeyCode::Backquote,
+      Some('[') => KeyCode::BracketLeft,
+      Some(']') => KeyCode::BracketRight,
+      _ => return None,
+    },
+  })
+}
+
+#[test]
+fn test_vk_to_key_round_trip() {
+  let keys = [
+    KeyCode::KeyA,
+    KeyCode::KeyM,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit9,
+    KeyCode::Comma,
+    KeyCode::Minus,
+    KeyCode::Period,
+    KeyCode::Equal,
+    KeyCode::Semicolon,
+    KeyCode::Slash,
+    KeyCode::Backslash,
+    KeyCode::Quote,
+    KeyCode::Backquote,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Backspace,
+    KeyCode::Tab,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Pause,
+    KeyCode::CapsLock,
+    KeyCode::KanaMode,
+    KeyCode::Escape,
+    KeyCode::NonConvert,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::End,
+    KeyCode::Home,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowDown,
+    KeyCode::PrintScreen,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Help,
+    KeyCode::ContextMenu,
+    KeyCode::F1,
+    KeyCode::F12,
+    KeyCode::F13,
+    KeyCode::F24,
+    KeyCode::Numpad0,
+    KeyCode::Numpad9,
+    KeyCode::NumpadAdd,
+    KeyCode::NumpadSubtract,
+    KeyCode::NumpadMultiply,
+    KeyCode::NumpadDivide,
+    KeyCode::NumpadDecimal,
+    KeyCode::NumLock,
+    KeyCode::ScrollLock,
+    KeyCode::BrowserBack,
+    KeyCode::BrowserForward,
+    KeyCode::BrowserRefresh,
+    KeyCode::BrowserStop,
+    KeyCode::BrowserSearch,
+    KeyCode::BrowserFavorites,
+    KeyCode::BrowserHome,
+    KeyCode::AudioVolumeMute,
+    KeyCode::AudioVolumeDown,
+    KeyCode::AudioVolumeUp,
+    KeyCode::MediaTrackNext,
+    KeyCode::MediaTrackPrevious,
+    KeyCode::MediaStop,
+    KeyCode::MediaPlayPause,
+    KeyCode::LaunchMail,
+    KeyCode::Convert,
+  ];
+
+  for key in keys {
+    let vk = key_to_vk(&key).unwrap();
+    // punctuation that requires a modifier on the active layout has no key of its own
+    if vk >> 8 != 0 {
+      continue;
+    }
+    assert_eq!(vk_to_key(vk as i32, false), Some(key));
+  }
+
+  assert_eq!(
+    vk_to_key(VK_CONTROL as i32, true),
+    Some(KeyCode::ControlRight)
+  );
+  assert_eq!(
+    vk_to_key(VK_CONTROL as i32, false),
+    Some(KeyCode::ControlLeft)
+  );
+  assert_eq!(vk_to_key(VK_MENU as i32, true), Some(KeyCode::AltRight));
+  assert_eq!(
+    vk_to_key(VK_RETURN as i32, true),
+    Some(KeyCode::NumpadEnter)
+  );
+}
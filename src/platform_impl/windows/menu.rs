@@ -1,11 +1,27 @@
 // Copyright 2019-2021 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::HashMap, fmt, sync::Mutex};
+use std::{
+  cell::RefCell,
+  collections::{HashMap, HashSet},
+  fmt, io, ptr,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+};
 
 use windows::Win32::{
-  Foundation::{HWND, LPARAM, LRESULT, PSTR, PWSTR, WPARAM},
+  Foundation::{BOOL, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, PWSTR, RECT, WPARAM},
+  Graphics::Gdi::{
+    DeleteObject, InvalidateRect, MonitorFromRect, HBITMAP, MONITOR_DEFAULTTONEAREST,
+  },
+  System::Threading::GetCurrentThreadId,
   UI::{
+    Controls::{
+      DRAWITEMSTRUCT, MEASUREITEMSTRUCT, ODS_CHECKED, ODS_DISABLED, ODS_GRAYED, ODS_SELECTED,
+      ODT_MENU,
+    },
     Input::KeyboardAndMouse::*,
     Shell::*,
     WindowsAndMessaging::{self as win32wm, *},
@@ -14,19 +30,38 @@ use windows::Win32::{
 
 use crate::{
   accelerator::Accelerator,
+  dpi::PhysicalSize,
+  error::OsError as RootOsError,
   event::{Event, WindowEvent},
-  keyboard::{KeyCode, ModifiersState},
-  menu::{CustomMenuItem, MenuId, MenuItem, MenuType},
+  keyboard::ModifiersState,
+  menu::{CustomMenuItem, HighlightedMenuItem, MenuError, MenuId, MenuItem, MenuType},
   window::WindowId as RootWindowId,
 };
 
-use super::{accelerator::register_accel, keyboard::key_to_vk, util, WindowId};
+use super::{
+  accelerator::{is_translating_repeat, is_window_accel, register_accel, unregister_accel},
+  dpi::{hwnd_dpi, system_metrics_for_dpi},
+  event_loop::{EventLoopWindowTarget, CONTEXT_MENU_MSG_ID},
+  keyboard::key_to_vk,
+  keyboard_layout::LAYOUT_CACHE,
+  monitor, util, OsError, WindowId,
+};
 
 #[derive(Copy, Clone)]
 struct AccelWrapper(ACCEL);
 impl fmt::Debug for AccelWrapper {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-    f.pad(&format!(""))
+    let flags = self.0.fVirt as u32;
+    let modifiers: Vec<&str> = [(FCONTROL, "Ctrl"), (FALT, "Alt"), (FSHIFT, "Shift")]
+      .iter()
+      .filter(|(flag, _)| flags & flag != 0)
+      .map(|(_, name)| *name)
+      .collect();
+    f.debug_struct("AccelWrapper")
+      .field("key", &format_args!("{:#04x}", self.0.key))
+      .field("modifiers", &modifiers)
+      .field("cmd", &self.0.cmd)
+      .finish()
   }
 }
 
@@ -40,32 +75,328 @@ const QUIT_ID: usize = 5007;
 const MINIMIZE_ID: usize = 5008;
 
 lazy_static! {
-  static ref MENU_IDS: Mutex<Vec<u16>> = Mutex::new(vec![]);
+  // The state of every menu created by `Menu`, by handle. Mirrors share the state of their
+  // original.
+  static ref MENU_STATES: Mutex<HashMap<isize, SharedMenuState>> = Mutex::new(HashMap::new());
+  // The menu attached to each window, to update its table when an accelerator changes.
+  static ref MENU_WINDOWS: Mutex<HashMap<isize, isize>> = Mutex::new(HashMap::new());
+  // Id sent with `Event::MenuWillOpen` for each popup menu that asked for it.
+  static ref WILL_OPEN_IDS: Mutex<HashMap<isize, u16>> = Mutex::new(HashMap::new());
+  // Every `HMENU` created by `Menu::mirror`, mapped to all the handles it stays in sync with
+  // (itself included).
+  static ref MENU_MIRRORS: Mutex<HashMap<isize, Vec<isize>>> = Mutex::new(HashMap::new());
+  // Items hidden with `set_visible`, by menu.
+  static ref HIDDEN_ITEMS: Mutex<HashMap<isize, Vec<HiddenItem>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Clone)]
+struct OwnerDrawnItem {
+  size: PhysicalSize<u32>,
+  // shared so it can be called without holding the lock of the menu state
+  draw: Arc<dyn Fn(&MenuItemDrawContext) + Send + Sync>,
+}
+
+// A custom item removed from its menu by `set_visible(false)`.
+struct HiddenItem {
+  id: u16,
+  // position among all the items of the menu, hidden ones included
+  slot: u32,
+  info: MENUITEMINFOW,
+  // owns the buffer `info.dwTypeData` points to
+  title: Vec<u16>,
+}
+
+unsafe impl Send for HiddenItem {}
+
+// Item data of the separators inserted by `Menu::finalize_groups`, so they can be told apart
+// from the ones added by the user.
+const GROUP_SEPARATOR_DATA: usize = usize::MAX;
+// Item data of the labels added by `Menu::add_section_header`.
+const SECTION_HEADER_DATA: usize = usize::MAX - 1;
+
+// Signature the MSAA menu proxy looks for at the start of the item data.
+const MSAA_MENU_SIG: u32 = 0xAA0DF00D;
+
+// Layout of `MSAAMENUINFO`. When the `dwItemData` of a menu item points to this struct,
+// the system accessibility proxy (and UI Automation, which wraps it for menus) reports
+// `text` as the item name instead of the visible label.
+#[repr(C)]
+struct AccessibleName {
+  signature: u32,
+  len: u32,
+  text: *mut u16,
+  // owns the buffer `text` points to
+  buffer: Vec<u16>,
+}
+
+unsafe impl Send for AccessibleName {}
+
+impl AccessibleName {
+  fn new(name: &str) -> Box<AccessibleName> {
+    let mut buffer: Vec<u16> = name.encode_utf16().collect();
+    let len = buffer.len() as u32;
+    buffer.push(0);
+    let mut accessible_name = Box::new(AccessibleName {
+      signature: MSAA_MENU_SIG,
+      len,
+      text: ptr::null_mut(),
+      buffer,
+    });
+    accessible_name.text = accessible_name.buffer.as_mut_ptr();
+    accessible_name
+  }
+
+  // The item data pointing to the name, which must outlive the item.
+  fn item_data(&self) -> usize {
+    self as *const AccessibleName as usize
+  }
+}
+
+// State of the menu loop currently running on this thread, if any.
+struct MenuLoopState {
+  hook: HHOOK,
+  owner: HWND,
+  // The popup and the command id of the item currently highlighted, as reported by `WM_MENUSELECT`.
+  highlighted: Option<(HMENU, u16)>,
+}
+
+thread_local! {
+  static MENU_LOOP: RefCell<Option<MenuLoopState>> = RefCell::new(None);
+}
+
+/// Where and in which state an item added with [`MenuExtWindows::add_owner_drawn_item`] is
+/// drawn.
+///
+/// [`MenuExtWindows::add_owner_drawn_item`]: crate::platform::windows::MenuExtWindows::add_owner_drawn_item
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct MenuItemDrawContext {
+  /// The `HDC` to draw with.
+  pub hdc: *mut libc::c_void,
+  /// The left edge of the item in `hdc`.
+  pub left: i32,
+  /// The top edge of the item in `hdc`.
+  pub top: i32,
+  /// The right edge of the item in `hdc`, exclusive.
+  pub right: i32,
+  /// The bottom edge of the item in `hdc`, exclusive.
+  pub bottom: i32,
+  /// Whether the item is highlighted, by the mouse or the keyboard.
+  pub selected: bool,
+  /// Whether the item is disabled.
+  pub disabled: bool,
+  /// Whether the item is checked.
+  pub checked: bool,
+}
+
+/// An item of a menu, as returned by [`MenuExtWindows::items`].
+///
+/// [`MenuExtWindows::items`]: crate::platform::windows::MenuExtWindows::items
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuEntry {
+  /// A custom or native item. `title` doesn't include the accelerator text.
+  Item {
+    position: u32,
+    id: MenuId,
+    title: String,
+  },
+  /// A submenu added with `add_submenu`.
+  Submenu { position: u32, title: String },
+  /// A separator.
+  Separator { position: u32 },
+  /// A label added with
+  /// [`add_section_header`](crate::platform::windows::MenuExtWindows::add_section_header).
+  SectionHeader { position: u32, label: String },
+}
+
+/// Whether the accelerator of a menu item is displayed next to its title.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AcceleratorText {
+  /// Always display the accelerator.
+  Show,
+  /// Never display the accelerator.
+  Hide,
+  /// Display the accelerator only if the user relies on the keyboard rather than the mouse, as
+  /// set by the keyboard preference accessibility setting, read when the item is added.
+  System,
+}
+
+impl Default for AcceleratorText {
+  fn default() -> Self {
+    AcceleratorText::Show
+  }
 }
 
 pub struct MenuHandler {
   window_id: Option<RootWindowId>,
   menu_type: MenuType,
   event_sender: Box<dyn Fn(Event<'static, ()>)>,
+  suppress_beep: bool,
+  edit_item_events: bool,
+  edit_item_filter: Option<EditItemFilter>,
+  window_item_events: bool,
+  hmenu: HMENU,
+  state: SharedMenuState,
+  // The menu handed over by `attach`, destroyed along with the window unless it is its menu bar.
+  owned_menu: Option<HMENU>,
+}
+
+// State of the custom items of a menu, shared with its mirrors and the handler it is attached to.
+#[derive(Default)]
+struct MenuState {
+  // Ids of the custom items, including the ones of the submenus added so far.
+  ids: HashSet<u16>,
+  // Accelerators of the items of this menu, submenus excluded. The table of a window is built
+  // from the ones of every menu of its menu bar.
+  accels: HashMap<u16, AccelWrapper>,
+  accelerator_text: AcceleratorText,
+  // Items toggled in place instead of closing the menu when clicked.
+  keep_open: HashSet<u16>,
+  // The names the items point to with their item data, freed along with the menu.
+  accessible_names: HashMap<u16, Box<AccessibleName>>,
+  // Group of the items, for `Menu::finalize_groups`.
+  groups: HashMap<u16, u32>,
+  // Items whose accelerator fires once per key press, ignoring keyboard auto-repeat.
+  no_repeat: HashSet<u16>,
+  // First and last id of the radio group of an item.
+  radio_groups: HashMap<u16, (u16, u16)>,
+  // Bitmaps set with `set_icon`, freed when replaced or along with the menu.
+  bitmaps: HashMap<u16, isize>,
+  // The `.ico` buffer of each item with an icon, to load it again when the DPI changes.
+  icons: HashMap<u16, Vec<u8>>,
+  // The checked and unchecked bitmaps of each item with custom checkmarks, freed the same way.
+  check_bitmaps: HashMap<u16, (isize, isize)>,
+  // Size and draw callback of the owner-drawn items.
+  owner_drawn: HashMap<u16, OwnerDrawnItem>,
+  // Hints set with `set_help_text`, sent along with `Event::MenuItemHighlighted`.
+  help_texts: HashMap<u16, String>,
+}
+
+impl MenuState {
+  // A copy of the state for `copy`, a duplicate of the menu, which gets its own accessible names
+  // and bitmaps.
+  unsafe fn duplicate(&self, copy: HMENU) -> MenuState {
+    let accessible_names = self
+      .accessible_names
+      .iter()
+      .map(|(id, name)| {
+        let name =
+          AccessibleName::new(&String::from_utf16_lossy(&name.buffer[..name.len as usize]));
+        set_menu_item_data(copy, *id, name.item_data());
+        (*id, name)
+      })
+      .collect();
+    let bitmaps = self
+      .bitmaps
+      .iter()
+      .map(|(id, hbitmap)| {
+        let hbitmap = copy_bitmap(*hbitmap);
+        let info = MENUITEMINFOW {
+          cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+          fMask: MIIM_BITMAP,
+          hbmpItem: HBITMAP(hbitmap),
+          ..Default::default()
+        };
+        SetMenuItemInfoW(copy, *id as u32, false, &info);
+        (*id, hbitmap)
+      })
+      .collect();
+    let check_bitmaps = self
+      .check_bitmaps
+      .iter()
+      .map(|(id, (checked, unchecked))| {
+        let (checked, unchecked) = (copy_bitmap(*checked), copy_bitmap(*unchecked));
+        SetMenuItemBitmaps(
+          copy,
+          *id as u32,
+          MF_BYCOMMAND,
+          HBITMAP(unchecked),
+          HBITMAP(checked),
+        );
+        (*id, (checked, unchecked))
+      })
+      .collect();
+    MenuState {
+      ids: self.ids.clone(),
+      accels: self.accels.clone(),
+      accelerator_text: self.accelerator_text,
+      keep_open: self.keep_open.clone(),
+      accessible_names,
+      groups: self.groups.clone(),
+      no_repeat: self.no_repeat.clone(),
+      radio_groups: self.radio_groups.clone(),
+      bitmaps,
+      icons: self.icons.clone(),
+      check_bitmaps,
+      owner_drawn: self.owner_drawn.clone(),
+      help_texts: self.help_texts.clone(),
+    }
+  }
+
+  // Frees the bitmaps, once no menu displays them anymore.
+  unsafe fn delete_bitmaps(&mut self) {
+    for (_, hbitmap) in self.bitmaps.drain() {
+      DeleteObject(HBITMAP(hbitmap));
+    }
+    for (_, (checked, unchecked)) in self.check_bitmaps.drain() {
+      DeleteObject(HBITMAP(checked));
+      DeleteObject(HBITMAP(unchecked));
+    }
+  }
 }
 
+impl fmt::Debug for MenuState {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+    f.debug_struct("MenuState")
+      .field("ids", &self.ids)
+      .field("accels", &self.accels)
+      .finish_non_exhaustive()
+  }
+}
+
+type SharedMenuState = Arc<Mutex<MenuState>>;
+
 impl MenuHandler {
-  pub fn new(
-    event_sender: Box<dyn Fn(Event<'static, ()>)>,
+  // The events are sent to the event loop of `event_loop`, whatever its user event type is, as
+  // menus never send user events.
+  pub fn new<T: 'static>(
+    event_loop: &EventLoopWindowTarget<T>,
     menu_type: MenuType,
     window_id: Option<RootWindowId>,
   ) -> MenuHandler {
+    let event_loop_runner = event_loop.runner_shared.clone();
+    let event_sender = Box::new(move |event: Event<'static, ()>| {
+      if let Ok(event) = event.map_nonuser_event() {
+        unsafe { event_loop_runner.send_event(event) }
+      }
+    });
     MenuHandler {
       window_id,
       menu_type,
       event_sender,
+      suppress_beep: false,
+      edit_item_events: false,
+      edit_item_filter: None,
+      window_item_events: false,
+      hmenu: HMENU::default(),
+      state: SharedMenuState::default(),
+      owned_menu: None,
     }
   }
+
+  pub fn set_menu(&mut self, menu: &Menu) {
+    self.hmenu = menu.hmenu;
+    self.state = menu.state.clone();
+  }
+
   pub fn send_menu_event(&self, menu_id: u16) {
     (self.event_sender)(Event::MenuEvent {
       menu_id: MenuId(menu_id),
       origin: self.menu_type,
       window_id: self.window_id,
+      modifiers: LAYOUT_CACHE.lock().unwrap().get_agnostic_mods(),
     });
   }
 
@@ -82,50 +413,394 @@ impl MenuItemAttributes {
     MenuId(self.0)
   }
   pub fn set_enabled(&mut self, enabled: bool) {
-    unsafe {
-      EnableMenuItem(
-        self.1,
-        self.0 as u32,
-        match enabled {
-          true => MF_ENABLED,
-          false => MF_DISABLED,
-        },
-      );
+    for hmenu in linked_menus(self.1) {
+      unsafe {
+        EnableMenuItem(
+          hmenu,
+          self.0 as u32,
+          match enabled {
+            true => MF_ENABLED,
+            false => MF_DISABLED,
+          },
+        );
+      }
     }
   }
   pub fn set_title(&mut self, title: &str) {
+    for hmenu in linked_menus(self.1) {
+      unsafe {
+        let mut anno_title = title.to_string();
+        // keep the accelerator text, if any
+        if let Some(previous) = menu_item_title(hmenu, self.0) {
+          if let Some((_, accelerator)) = previous.split_once('\t') {
+            anno_title.push('\t');
+            anno_title.push_str(accelerator);
+          }
+        }
+
+        let mut anno_title = util::to_wstring(&anno_title);
+        let info = MENUITEMINFOW {
+          cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+          fMask: MIIM_STRING,
+          dwTypeData: PWSTR(anno_title.as_mut_ptr()),
+          ..Default::default()
+        };
+        SetMenuItemInfoW(hmenu, self.0 as u32, false, &info);
+      }
+    }
+  }
+  pub fn is_enabled(&self) -> bool {
+    self
+      .state()
+      .map_or(false, |state| state & (MF_GRAYED | MF_DISABLED) == 0)
+  }
+
+  pub fn is_selected(&self) -> bool {
+    self.state().map_or(false, |state| state & MF_CHECKED != 0)
+  }
+
+  fn state(&self) -> Option<u32> {
+    let state = unsafe { GetMenuState(self.1, self.0 as u32, MF_BYCOMMAND) };
+    if state != u32::MAX {
+      return Some(state);
+    }
+    // hidden items are kept out of the menu
+    HIDDEN_ITEMS
+      .lock()
+      .unwrap()
+      .get(&self.1 .0)?
+      .iter()
+      .find(|item| item.id == self.0)
+      .map(|item| item.info.fState)
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_visible(&mut self, visible: bool) {
+    for hmenu in linked_menus(self.1) {
+      unsafe {
+        match visible {
+          true => show_menu_item(hmenu, self.0),
+          false => hide_menu_item(hmenu, self.0),
+        }
+      }
+    }
+    // the accelerator of a hidden item doesn't fire
+    update_window_accels(self.1);
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_accelerator(&mut self, accelerator: Option<Accelerator>) {
+    let accel = accelerator
+      .clone()
+      .and_then(|accelerator| convert_accelerator(self.0, accelerator));
+    let show_text = match menu_state(self.1) {
+      Some(state) => {
+        let mut state = state.lock().unwrap();
+        match accel {
+          Some(accel) => state.accels.insert(self.0, AccelWrapper(accel)),
+          None => state.accels.remove(&self.0),
+        };
+        is_accelerator_text_shown(state.accelerator_text)
+      }
+      None => true,
+    };
+
+    for hmenu in linked_menus(self.1) {
+      unsafe {
+        let title = match menu_item_title(hmenu, self.0) {
+          Some(title) => title,
+          None => continue,
+        };
+        let label = match title.split_once('\t') {
+          Some((label, _)) => label,
+          None => title.as_str(),
+        };
+        let mut anno_title = label.to_string();
+        if let (Some(accelerator), true) = (&accelerator, show_text) {
+          anno_title.push('\t');
+          anno_title.push_str(&accelerator.to_string());
+        }
+
+        let mut anno_title = util::to_wstring(&anno_title);
+        let info = MENUITEMINFOW {
+          cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+          fMask: MIIM_STRING,
+          dwTypeData: PWSTR(anno_title.as_mut_ptr()),
+          ..Default::default()
+        };
+        SetMenuItemInfoW(hmenu, self.0 as u32, false, &info);
+      }
+    }
+
+    update_window_accels(self.1);
+  }
+
+  pub fn set_selected(&mut self, selected: bool) {
+    let radio_group =
+      menu_state(self.1).and_then(|state| state.lock().unwrap().radio_groups.get(&self.0).copied());
+    if let (true, Some((first, last))) = (selected, radio_group) {
+      for hmenu in linked_menus(self.1) {
+        unsafe { check_radio_item(hmenu, first, last, self.0) };
+      }
+      return;
+    }
+
+    for hmenu in linked_menus(self.1) {
+      unsafe {
+        CheckMenuItem(
+          hmenu,
+          self.0 as u32,
+          match selected {
+            true => MF_CHECKED,
+            false => MF_UNCHECKED,
+          },
+        );
+      }
+    }
+  }
+
+  pub fn set_icon(&mut self, icon: Vec<u8>) -> Result<(), RootOsError> {
     unsafe {
-      let info = MENUITEMINFOA {
-        cbSize: std::mem::size_of::<MENUITEMINFOA>() as _,
-        fMask: MIIM_STRING,
-        dwTypeData: PSTR(String::from(title).as_mut_ptr()),
+      let (width, height) = menu_icon_size(self.1);
+      let hbitmap = load_menu_bitmap(&icon, width, height)?;
+
+      let info = MENUITEMINFOW {
+        cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+        fMask: MIIM_BITMAP,
+        hbmpItem: hbitmap,
         ..Default::default()
       };
+      let mut applied = false;
+      for hmenu in linked_menus(self.1) {
+        applied |= SetMenuItemInfoW(hmenu, self.0 as u32, false, &info).as_bool();
+      }
+      if !applied {
+        let error = io::Error::last_os_error();
+        DeleteObject(hbitmap);
+        return Err(os_error!(OsError::IoError(error)));
+      }
 
-      SetMenuItemInfoA(self.1, self.0 as u32, false, &info);
+      // the menu no longer uses the previous bitmap
+      let previous = menu_state(self.1).and_then(|state| {
+        let mut state = state.lock().unwrap();
+        state.icons.insert(self.0, icon);
+        state.bitmaps.insert(self.0, hbitmap.0)
+      });
+      if let Some(previous) = previous {
+        DeleteObject(HBITMAP(previous));
+      }
     }
+    Ok(())
   }
-  pub fn set_selected(&mut self, selected: bool) {
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_check_bitmaps(
+    &mut self,
+    checked: Vec<u8>,
+    unchecked: Vec<u8>,
+  ) -> Result<(), RootOsError> {
     unsafe {
-      CheckMenuItem(
-        self.1,
-        self.0 as u32,
-        match selected {
-          true => MF_CHECKED,
-          false => MF_UNCHECKED,
-        },
+      let (width, height) = (
+        GetSystemMetrics(SM_CXMENUCHECK),
+        GetSystemMetrics(SM_CYMENUCHECK),
       );
+      let checked = load_menu_bitmap(&checked, width, height)?;
+      let unchecked = match load_menu_bitmap(&unchecked, width, height) {
+        Ok(unchecked) => unchecked,
+        Err(error) => {
+          DeleteObject(checked);
+          return Err(error);
+        }
+      };
+
+      let mut applied = false;
+      for hmenu in linked_menus(self.1) {
+        applied |=
+          SetMenuItemBitmaps(hmenu, self.0 as u32, MF_BYCOMMAND, unchecked, checked).as_bool();
+      }
+      if !applied {
+        let error = io::Error::last_os_error();
+        DeleteObject(checked);
+        DeleteObject(unchecked);
+        return Err(os_error!(OsError::IoError(error)));
+      }
+
+      // the menu no longer uses the previous bitmaps
+      let previous = menu_state(self.1).and_then(|state| {
+        state
+          .lock()
+          .unwrap()
+          .check_bitmaps
+          .insert(self.0, (checked.0, unchecked.0))
+      });
+      if let Some((checked, unchecked)) = previous {
+        DeleteObject(HBITMAP(checked));
+        DeleteObject(HBITMAP(unchecked));
+      }
+    }
+    Ok(())
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_accessible_name(&mut self, name: &str) {
+    let state = match menu_state(self.1) {
+      Some(state) => state,
+      None => return,
+    };
+    let mut state = state.lock().unwrap();
+    let (data, previous) = if name.is_empty() {
+      (0, state.accessible_names.remove(&self.0))
+    } else {
+      let accessible_name = AccessibleName::new(name);
+      let data = accessible_name.item_data();
+      (data, state.accessible_names.insert(self.0, accessible_name))
+    };
+
+    for hmenu in linked_menus(self.1) {
+      unsafe { set_menu_item_data(hmenu, self.0, data) };
+    }
+
+    // only free the previous name once the menu no longer points to it
+    drop(previous);
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_accelerator_repeat(&mut self, repeat: bool) {
+    if let Some(state) = menu_state(self.1) {
+      let mut state = state.lock().unwrap();
+      if repeat {
+        state.no_repeat.remove(&self.0);
+      } else {
+        state.no_repeat.insert(self.0);
+      }
+    }
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_help_text(&mut self, text: &str) {
+    if let Some(state) = menu_state(self.1) {
+      let mut state = state.lock().unwrap();
+      if text.is_empty() {
+        state.help_texts.remove(&self.0);
+      } else {
+        state.help_texts.insert(self.0, text.to_string());
+      }
+    }
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_group(&mut self, group: u32) {
+    if let Some(state) = menu_state(self.1) {
+      state.lock().unwrap().groups.insert(self.0, group);
+    }
+  }
+
+  // Available only with CustomMenuItemExtWindows
+  pub fn set_keep_open_on_click(&mut self, keep_open: bool) {
+    if let Some(state) = menu_state(self.1) {
+      let mut state = state.lock().unwrap();
+      if keep_open {
+        state.keep_open.insert(self.0);
+      } else {
+        state.keep_open.remove(&self.0);
+      }
+    }
+  }
+}
+
+// A submenu has no command id, so it is addressed by its position in the parent menu.
+#[derive(Debug, Clone, Copy)]
+pub struct Submenu {
+  hmenu: HMENU,
+  position: u32,
+}
+
+impl Submenu {
+  // Available only with MenuExtWindows
+  pub fn set_enabled(&mut self, enabled: bool) {
+    for hmenu in linked_menus(self.hmenu) {
+      unsafe {
+        EnableMenuItem(
+          hmenu,
+          self.position,
+          MF_BYPOSITION
+            | match enabled {
+              true => MF_ENABLED,
+              false => MF_GRAYED,
+            },
+        );
+      }
     }
   }
 
-  // todo: set custom icon to the menu item
-  pub fn set_icon(&mut self, _icon: Vec<u8>) {}
+  // Available only with MenuExtWindows
+  pub fn set_selected(&mut self, selected: bool) {
+    for hmenu in linked_menus(self.hmenu) {
+      unsafe {
+        CheckMenuItem(
+          hmenu,
+          self.position,
+          MF_BYPOSITION
+            | match selected {
+              true => MF_CHECKED,
+              false => MF_UNCHECKED,
+            },
+        );
+      }
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
 pub struct Menu {
   hmenu: HMENU,
-  accels: HashMap<u16, AccelWrapper>,
+  validate_accelerators: bool,
+  unreachable_accelerators: Vec<(MenuId, Accelerator)>,
+  validate_mnemonics: bool,
+  mnemonic_conflicts: Vec<(MenuId, char)>,
+  rtl: bool,
+  suppress_beep: bool,
+  edit_item_events: bool,
+  edit_item_filter: Option<EditItemFilter>,
+  window_item_events: bool,
+  state: SharedMenuState,
+  ownership: Arc<MenuOwnership>,
+}
+
+// Decides whether the application handles an edit item itself, see
+// `MenuExtWindows::with_edit_item_filter`.
+#[derive(Clone)]
+struct EditItemFilter(Arc<dyn Fn(MenuId) -> bool + Send + Sync>);
+
+impl fmt::Debug for EditItemFilter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+    f.pad("EditItemFilter")
+  }
+}
+
+// Destroys the menu once the last `Menu` sharing it is dropped, unless it was handed over to a
+// parent menu, a window or a system tray, which then owns it.
+#[derive(Debug)]
+struct MenuOwnership {
+  hmenu: HMENU,
+  detached: AtomicBool,
+}
+
+impl MenuOwnership {
+  fn new(hmenu: HMENU) -> Arc<Self> {
+    Arc::new(MenuOwnership {
+      hmenu,
+      detached: AtomicBool::new(true),
+    })
+  }
+}
+
+impl Drop for MenuOwnership {
+  fn drop(&mut self) {
+    if self.detached.load(Ordering::SeqCst) {
+      unsafe { destroy_menu(self.hmenu) };
+    }
+  }
 }
 
 unsafe impl Send for Menu {}
@@ -139,22 +814,29 @@ impl Default for Menu {
 
 impl Menu {
   pub fn new() -> Self {
-    unsafe {
-      let hmenu = CreateMenu();
-      Menu {
-        hmenu,
-        accels: HashMap::default(),
-      }
-    }
+    Menu::from_hmenu(unsafe { CreateMenu() })
   }
 
   pub fn new_popup_menu() -> Self {
-    unsafe {
-      let hmenu = CreatePopupMenu();
-      Menu {
-        hmenu,
-        accels: HashMap::default(),
-      }
+    Menu::from_hmenu(unsafe { CreatePopupMenu() })
+  }
+
+  fn from_hmenu(hmenu: HMENU) -> Self {
+    let state = SharedMenuState::default();
+    MENU_STATES.lock().unwrap().insert(hmenu.0, state.clone());
+    Menu {
+      hmenu,
+      validate_accelerators: false,
+      unreachable_accelerators: Vec::new(),
+      validate_mnemonics: false,
+      mnemonic_conflicts: Vec::new(),
+      rtl: false,
+      suppress_beep: false,
+      edit_item_events: false,
+      edit_item_filter: None,
+      window_item_events: false,
+      state,
+      ownership: MenuOwnership::new(hmenu),
     }
   }
 
@@ -162,79 +844,718 @@ impl Menu {
     self.hmenu
   }
 
-  // Get the accels table
-  pub(crate) fn accels(&self) -> Option<Vec<ACCEL>> {
-    if self.accels.is_empty() {
-      return None;
+  // Hands the menu over to its new owner, which destroys it instead of the last `Menu` dropped.
+  pub fn release(&self) {
+    self.ownership.detached.store(false, Ordering::SeqCst);
+  }
+
+  fn is_released(&self) -> bool {
+    !self.ownership.detached.load(Ordering::SeqCst)
+  }
+
+  // Available only with MenuExtWindows
+  pub fn remove_item(&mut self, item: &CustomMenuItem) {
+    let MenuItemAttributes(id, item_menu) = item.0;
+    let mut removed = false;
+    for hmenu in linked_menus(item_menu) {
+      removed |= unsafe { RemoveMenu(hmenu, id as u32, MF_BYCOMMAND) }.as_bool();
+      if let Some(hidden) = HIDDEN_ITEMS.lock().unwrap().get_mut(&hmenu.0) {
+        let count = hidden.len();
+        hidden.retain(|hidden_item| hidden_item.id != id);
+        removed |= hidden.len() != count;
+      }
+    }
+    if !removed {
+      return;
+    }
+
+    if let Some(state) = menu_state(item_menu) {
+      let mut state = state.lock().unwrap();
+      state.accels.remove(&id);
+      state.ids.remove(&id);
+      state.keep_open.remove(&id);
+      state.accessible_names.remove(&id);
+      state.groups.remove(&id);
+      state.no_repeat.remove(&id);
+      if let Some(hbitmap) = state.bitmaps.remove(&id) {
+        unsafe { DeleteObject(HBITMAP(hbitmap)) };
+      }
+      state.icons.remove(&id);
+      state.owner_drawn.remove(&id);
+      state.help_texts.remove(&id);
+      if let Some((checked, unchecked)) = state.check_bitmaps.remove(&id) {
+        unsafe {
+          DeleteObject(HBITMAP(checked));
+          DeleteObject(HBITMAP(unchecked));
+        }
+      }
     }
-    Some(self.accels.values().cloned().map(|d| d.0).collect())
+    // the table of the window is already registered if the menu was attached to one
+    update_window_accels(item_menu);
+
+    self.state.lock().unwrap().ids.remove(&id);
   }
 
-  pub fn add_item(
+  // Available only with MenuExtWindows
+  #[allow(clippy::too_many_arguments)]
+  pub fn add_owner_drawn_item(
     &mut self,
     menu_id: MenuId,
     title: &str,
     accelerators: Option<Accelerator>,
     enabled: bool,
     selected: bool,
-    _menu_type: MenuType,
+    size: PhysicalSize<u32>,
+    draw: Option<Box<dyn Fn(&MenuItemDrawContext) + Send + Sync>>,
+    menu_type: MenuType,
   ) -> CustomMenuItem {
+    let item = self.add_item(menu_id, title, accelerators, enabled, selected, menu_type);
+    // without a callback, the title is drawn as usual
+    if let Some(draw) = draw {
+      let info = MENUITEMINFOW {
+        cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+        fMask: MIIM_FTYPE,
+        fType: MFT_OWNERDRAW,
+        ..Default::default()
+      };
+      unsafe { SetMenuItemInfoW(self.hmenu, menu_id.0 as u32, false, &info) };
+      self.state.lock().unwrap().owner_drawn.insert(
+        menu_id.0,
+        OwnerDrawnItem {
+          size,
+          draw: Arc::from(draw),
+        },
+      );
+    }
+    item
+  }
+
+  // Available only with MenuExtWindows
+  pub fn mirror(&self, menu_type: MenuType) -> Menu {
+    let hmenu = unsafe { copy_hmenu(self.hmenu, menu_type == MenuType::ContextMenu, true) };
+    Menu {
+      hmenu,
+      ownership: MenuOwnership::new(hmenu),
+      ..self.clone()
+    }
+  }
+
+  // Available only with MenuExtWindows
+  pub fn duplicate(&self, menu_type: MenuType) -> Menu {
+    let hmenu = unsafe { copy_hmenu(self.hmenu, menu_type == MenuType::ContextMenu, false) };
+    Menu {
+      hmenu,
+      state: menu_state(hmenu).unwrap_or_default(),
+      ownership: MenuOwnership::new(hmenu),
+      ..self.clone()
+    }
+  }
+
+  // Available only with WindowExtWindows
+  pub fn show_context_menu(&self, window: HWND, x: i32, y: i32) {
     unsafe {
-      let mut flags = MF_STRING;
-      if !enabled {
-        flags |= MF_GRAYED;
+      // the popup only closes when clicking outside of it if the window is in the foreground
+      SetForegroundWindow(window);
+      let command = TrackPopupMenu(
+        self.hmenu,
+        TPM_RETURNCMD | TPM_RIGHTBUTTON,
+        x,
+        y,
+        0,
+        window,
+        ptr::null(),
+      )
+      .0 as u16;
+      if command == 0 {
+        return;
       }
-      if selected {
-        flags |= MF_CHECKED;
+
+      // the event is sent from the event loop, once the popup is closed
+      if self.state.lock().unwrap().ids.contains(&command) {
+        PostMessageW(
+          window,
+          *CONTEXT_MENU_MSG_ID,
+          WPARAM(command as _),
+          LPARAM(0),
+        );
+      } else if native_item_for_command(command, &HashSet::new()).is_some() {
+        // handled like the native items of the menu bar of the window
+        PostMessageW(window, WM_COMMAND, WPARAM(command as _), LPARAM(0));
       }
+    }
+  }
 
-      let mut anno_title = title.to_string();
-      // format title
-      if let Some(accelerators) = accelerators.clone() {
-        anno_title.push('\t');
-        format_hotkey(accelerators, &mut anno_title);
+  // Available only with MenuExtWindows
+  pub fn track_popup_at_item(
+    &self,
+    window: HWND,
+    anchor: &CustomMenuItem,
+  ) -> Result<(), RootOsError> {
+    let MenuItemAttributes(id, anchor_menu) = anchor.0;
+    unsafe {
+      let position = (0..GetMenuItemCount(anchor_menu).max(0))
+        .find(|position| GetMenuItemID(anchor_menu, *position) == id as u32)
+        .ok_or_else(|| os_error!(OsError::CreationError("Anchor item not found in its menu")))?;
+
+      let mut item_rect = RECT::default();
+      if !GetMenuItemRect(window, anchor_menu, position as u32, &mut item_rect).as_bool() {
+        return Err(os_error!(OsError::IoError(io::Error::last_os_error())));
       }
 
-      AppendMenuW(self.hmenu, flags, menu_id.0 as _, anno_title);
+      let hmonitor = MonitorFromRect(&item_rect, MONITOR_DEFAULTTONEAREST);
+      let work_area = monitor::get_monitor_info(hmonitor)
+        .map(|info| info.monitorInfo.rcWork)
+        .map_err(|err| os_error!(OsError::IoError(err)))?;
 
-      // add our accels
-      if let Some(accelerators) = accelerators {
-        if let Some(accelerators) = convert_accelerator(menu_id.0, accelerators) {
-          self.accels.insert(menu_id.0, AccelWrapper(accelerators));
+      // Items of a menu bar open the popup below them, like their own submenus do, other items
+      // open it on their side. Flip to the opposite side when it has more room in the work area.
+      let (flags, x, y) = if GetMenu(window) == anchor_menu {
+        if work_area.bottom - item_rect.bottom >= item_rect.top - work_area.top {
+          (
+            TPM_TOPALIGN | TPM_LEFTALIGN,
+            item_rect.left,
+            item_rect.bottom,
+          )
+        } else {
+          (
+            TPM_BOTTOMALIGN | TPM_LEFTALIGN,
+            item_rect.left,
+            item_rect.top,
+          )
         }
+      } else if work_area.right - item_rect.right >= item_rect.left - work_area.left {
+        (TPM_TOPALIGN | TPM_LEFTALIGN, item_rect.right, item_rect.top)
+      } else {
+        (TPM_TOPALIGN | TPM_RIGHTALIGN, item_rect.left, item_rect.top)
+      };
+
+      // the system still moves the popup if it doesn't fit, make sure it never covers the anchor
+      let params = TPMPARAMS {
+        cbSize: std::mem::size_of::<TPMPARAMS>() as _,
+        rcExclude: item_rect,
+      };
+
+      SetForegroundWindow(window);
+      if !TrackPopupMenuEx(self.hmenu, flags, x, y, window, &params).as_bool() {
+        return Err(os_error!(OsError::IoError(io::Error::last_os_error())));
       }
-      MENU_IDS.lock().unwrap().push(menu_id.0 as _);
-      CustomMenuItem(MenuItemAttributes(menu_id.0, self.hmenu))
     }
+    Ok(())
+  }
+
+  // Available only with MenuExtWindows
+  pub fn highlight_next(&self, window: HWND) {
+    unsafe { move_highlight(window, self.hmenu, true) }
+  }
+
+  // Available only with MenuExtWindows
+  pub fn highlight_previous(&self, window: HWND) {
+    unsafe { move_highlight(window, self.hmenu, false) }
   }
 
-  pub fn add_submenu(&mut self, title: &str, enabled: bool, mut submenu: Menu) {
+  // Available only with MenuExtWindows
+  pub fn activate_highlighted(&self, window: HWND) {
     unsafe {
-      let child_accels = std::mem::take(&mut submenu.accels);
-      self.accels.extend(child_accels);
+      let position = match highlighted_position(self.hmenu) {
+        Some(position) => position,
+        None => return,
+      };
+      let state = GetMenuState(self.hmenu, position, MF_BYPOSITION);
+      if state & (MF_GRAYED | MF_DISABLED | MF_SEPARATOR | MF_POPUP) != 0 {
+        return;
+      }
+      let id = GetMenuItemID(self.hmenu, position as i32) as u16;
 
-      let mut flags = MF_POPUP;
-      if !enabled {
-        flags |= MF_DISABLED;
+      if is_keep_open_item(self.hmenu, id) {
+        let check = if state & MF_CHECKED != 0 {
+          MF_UNCHECKED
+        } else {
+          MF_CHECKED
+        };
+        for hmenu in linked_menus(self.hmenu) {
+          CheckMenuItem(hmenu, id as u32, check);
+        }
+      } else {
+        HiliteMenuItem(window, self.hmenu, position, MF_BYPOSITION | MF_UNHILITE);
+        if MENU_LOOP.with(|state| state.borrow().is_some()) {
+          EndMenu();
+        }
       }
 
-      AppendMenuW(self.hmenu, flags, submenu.hmenu().0 as usize, title);
+      // dispatched exactly like a click on the item
+      SendMessageW(window, WM_COMMAND, WPARAM(id as usize), LPARAM(0));
     }
   }
 
-  pub fn add_native_item(
-    &mut self,
-    item: MenuItem,
-    _menu_type: MenuType,
-  ) -> Option<CustomMenuItem> {
-    match item {
-      MenuItem::Separator => {
-        unsafe {
-          AppendMenuW(self.hmenu, MF_SEPARATOR, 0, PWSTR::default());
-        };
-      }
-      MenuItem::Cut => unsafe {
-        AppendMenuW(self.hmenu, MF_STRING, CUT_ID, "&Cut\tCtrl+X");
+  // Available only with MenuExtWindows
+  pub fn set_accelerator_text(&mut self, accelerator_text: AcceleratorText) {
+    self.state.lock().unwrap().accelerator_text = accelerator_text;
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_edit_item_events(&mut self, send_events: bool) {
+    self.edit_item_events = send_events;
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_edit_item_filter(&mut self, filter: Box<dyn Fn(MenuId) -> bool + Send + Sync>) {
+    self.edit_item_filter = Some(EditItemFilter(Arc::from(filter)));
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_window_item_events(&mut self, send_events: bool) {
+    self.window_item_events = send_events;
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_suppress_beep(&mut self, suppress: bool) {
+    self.suppress_beep = suppress;
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_validate_accelerators(&mut self, validate: bool) {
+    self.validate_accelerators = validate;
+  }
+
+  // Available only with MenuExtWindows
+  pub fn unreachable_accelerators(&self) -> Vec<(MenuId, Accelerator)> {
+    self.unreachable_accelerators.clone()
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_layout_rtl(&mut self, rtl: bool) {
+    self.rtl = rtl;
+    unsafe { set_menu_rtl(self.hmenu, rtl) };
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_validate_mnemonics(&mut self, validate: bool) {
+    self.validate_mnemonics = validate;
+  }
+
+  // Available only with MenuExtWindows
+  pub fn mnemonic_conflicts(&self) -> Vec<(MenuId, char)> {
+    self.mnemonic_conflicts.clone()
+  }
+
+  // Available only with MenuExtWindows
+  pub fn finalize_groups(&mut self) {
+    unsafe {
+      // remove the separators of a previous run, so we can re-balance from scratch
+      for position in (0..GetMenuItemCount(self.hmenu).max(0) as u32).rev() {
+        if let Some(info) = get_menu_item_info(self.hmenu, position) {
+          if info.fType & MFT_SEPARATOR != 0 && info.dwItemData == GROUP_SEPARATOR_DATA {
+            DeleteMenu(self.hmenu, position, MF_BYPOSITION);
+          }
+        }
+      }
+
+      let groups = self.state.lock().unwrap().groups.clone();
+      // group of the last grouped item since the last separator
+      let mut previous_group = None;
+      let mut position = 0;
+      while (position as i32) < GetMenuItemCount(self.hmenu) {
+        let info = match get_menu_item_info(self.hmenu, position) {
+          Some(info) => info,
+          None => break,
+        };
+
+        if info.fType & MFT_SEPARATOR != 0 {
+          previous_group = None;
+        } else if info.hSubMenu.is_invalid() {
+          // items without a group never trigger a separator
+          if let Some(&group) = groups.get(&(info.wID as u16)) {
+            if previous_group.map_or(false, |previous| previous != group) {
+              let separator = MENUITEMINFOW {
+                cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+                fMask: MIIM_FTYPE | MIIM_DATA,
+                fType: MFT_SEPARATOR,
+                dwItemData: GROUP_SEPARATOR_DATA,
+                ..Default::default()
+              };
+              InsertMenuItemW(self.hmenu, position, true, &separator);
+              position += 1;
+            }
+            previous_group = Some(group);
+          }
+        }
+
+        position += 1;
+      }
+    }
+  }
+
+  // Get the accels table
+  pub(crate) fn accels(&self) -> Option<Vec<ACCEL>> {
+    menu_accels(self.hmenu)
+  }
+
+  pub fn add_item(
+    &mut self,
+    menu_id: MenuId,
+    title: &str,
+    accelerators: Option<Accelerator>,
+    enabled: bool,
+    selected: bool,
+    menu_type: MenuType,
+  ) -> CustomMenuItem {
+    let count = unsafe { GetMenuItemCount(self.hmenu) }.max(0) as u32;
+    self.insert_item_at(
+      count,
+      menu_id,
+      title,
+      accelerators,
+      enabled,
+      selected,
+      menu_type,
+    )
+  }
+
+  // Available only with MenuExtWindows
+  pub fn add_accelerator_only(&mut self, menu_id: MenuId, accelerator: Accelerator) {
+    if self.validate_accelerators && !is_accelerator_reachable(&accelerator) {
+      warn!(
+        "Accelerator {:?} of menu item {:?} can't be typed with the current keyboard layout",
+        accelerator, menu_id
+      );
+      self
+        .unreachable_accelerators
+        .push((menu_id, accelerator.clone()));
+    }
+    {
+      let mut state = self.state.lock().unwrap();
+      if let Some(accel) = convert_accelerator(menu_id.0, accelerator) {
+        state.accels.insert(menu_id.0, AccelWrapper(accel));
+      }
+      state.ids.insert(menu_id.0);
+    }
+    // the menu may already be attached to a window
+    update_window_accels(self.hmenu);
+  }
+
+  // Available only with MenuExtWindows
+  pub fn set_will_open_id(&mut self, menu_id: Option<MenuId>) {
+    let mut will_open_ids = WILL_OPEN_IDS.lock().unwrap();
+    match menu_id {
+      Some(menu_id) => will_open_ids.insert(self.hmenu.0, menu_id.0),
+      None => will_open_ids.remove(&self.hmenu.0),
+    };
+  }
+
+  // Available only with MenuExtWindows
+  pub fn add_section_header(&mut self, label: &str) {
+    unsafe {
+      // disabled without being grayed out, and without a command id so it never sends an event
+      AppendMenuW(
+        self.hmenu,
+        MF_STRING | MF_DISABLED,
+        0,
+        escape_mnemonics(label).as_str(),
+      );
+      // drawn in bold, and tagged so it can be told apart from the items
+      let info = MENUITEMINFOW {
+        cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+        fMask: MIIM_STATE | MIIM_DATA,
+        fState: MFS_DEFAULT | MF_DISABLED,
+        dwItemData: SECTION_HEADER_DATA,
+        ..Default::default()
+      };
+      let position = GetMenuItemCount(self.hmenu).max(1) as u32 - 1;
+      SetMenuItemInfoW(self.hmenu, position, true, &info);
+      if self.rtl {
+        set_menu_rtl(self.hmenu, true);
+      }
+    }
+  }
+
+  // Available only with MenuExtWindows
+  pub fn item_count(&self) -> u32 {
+    unsafe { GetMenuItemCount(self.hmenu) }.max(0) as u32
+  }
+
+  // Available only with MenuExtWindows
+  pub fn items(&self) -> Vec<MenuEntry> {
+    (0..self.item_count())
+      .filter_map(|position| {
+        let (info, title) = unsafe { copy_menu_item(self.hmenu, position) }?;
+        if info.fType & MFT_SEPARATOR != 0 {
+          return Some(MenuEntry::Separator { position });
+        }
+        let title = String::from_utf16_lossy(&title[..info.cch as usize]);
+        if info.dwItemData == SECTION_HEADER_DATA {
+          return Some(MenuEntry::SectionHeader {
+            position,
+            label: title.replace("&&", "&"),
+          });
+        }
+        // the accelerator text isn't part of the title given to the item
+        let title = match title.split_once('\t') {
+          Some((title, _)) => title.to_string(),
+          None => title,
+        };
+        Some(if info.hSubMenu.is_invalid() {
+          MenuEntry::Item {
+            position,
+            id: MenuId(info.wID as u16),
+            title,
+          }
+        } else {
+          MenuEntry::Submenu { position, title }
+        })
+      })
+      .collect()
+  }
+
+  // Available only with MenuExtWindows
+  pub fn add_item_escaped(
+    &mut self,
+    menu_id: MenuId,
+    title: &str,
+    accelerators: Option<Accelerator>,
+    enabled: bool,
+    selected: bool,
+    menu_type: MenuType,
+  ) -> CustomMenuItem {
+    self.add_item(
+      menu_id,
+      &escape_mnemonics(title),
+      accelerators,
+      enabled,
+      selected,
+      menu_type,
+    )
+  }
+
+  // Available only with MenuExtWindows
+  pub fn try_add_item(
+    &mut self,
+    menu_id: MenuId,
+    title: &str,
+    accelerators: Option<Accelerator>,
+    enabled: bool,
+    selected: bool,
+    menu_type: MenuType,
+  ) -> Result<CustomMenuItem, RootOsError> {
+    let count = unsafe { GetMenuItemCount(self.hmenu) }.max(0) as u32;
+    self.try_insert_item_at(
+      count,
+      menu_id,
+      title,
+      accelerators,
+      enabled,
+      selected,
+      menu_type,
+    )
+  }
+
+  // Available only with MenuExtWindows
+  pub fn insert_item_at(
+    &mut self,
+    index: u32,
+    menu_id: MenuId,
+    title: &str,
+    accelerators: Option<Accelerator>,
+    enabled: bool,
+    selected: bool,
+    menu_type: MenuType,
+  ) -> CustomMenuItem {
+    self
+      .try_insert_item_at(
+        index,
+        menu_id,
+        title,
+        accelerators,
+        enabled,
+        selected,
+        menu_type,
+      )
+      .unwrap_or_else(|error| {
+        warn!("Unable to add menu item {:?}: {}", menu_id, error);
+        CustomMenuItem(MenuItemAttributes(menu_id.0, self.hmenu))
+      })
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn try_insert_item_at(
+    &mut self,
+    index: u32,
+    menu_id: MenuId,
+    title: &str,
+    accelerators: Option<Accelerator>,
+    enabled: bool,
+    selected: bool,
+    _menu_type: MenuType,
+  ) -> Result<CustomMenuItem, RootOsError> {
+    unsafe {
+      let mut state = MFS_ENABLED;
+      if !enabled {
+        state |= MFS_GRAYED;
+      }
+      if selected {
+        state |= MFS_CHECKED;
+      }
+
+      let mut anno_title = title.to_string();
+      // format title
+      if let Some(accelerators) = accelerators.clone() {
+        if is_accelerator_text_shown(self.state.lock().unwrap().accelerator_text) {
+          anno_title.push('\t');
+          anno_title.push_str(&accelerators.to_string());
+        }
+      }
+
+      if self.validate_mnemonics {
+        if let Some(mnemonic) = mnemonic(title) {
+          let taken = (0..GetMenuItemCount(self.hmenu).max(0) as u32)
+            .filter_map(|position| copy_menu_item(self.hmenu, position))
+            .any(|(info, title)| {
+              mnemonic(&String::from_utf16_lossy(&title[..info.cch as usize])) == Some(mnemonic)
+            });
+          if taken {
+            warn!(
+              "Mnemonic '{}' of menu item {:?} is already used in the same menu",
+              mnemonic, menu_id
+            );
+            self.mnemonic_conflicts.push((menu_id, mnemonic));
+          }
+        }
+      }
+
+      // an out of range index appends the item
+      let index = index.min(GetMenuItemCount(self.hmenu).max(0) as u32);
+      let mut anno_title = util::to_wstring(&anno_title);
+      let info = MENUITEMINFOW {
+        cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+        fMask: MIIM_ID | MIIM_STRING | MIIM_STATE,
+        fState: state,
+        wID: menu_id.0 as _,
+        dwTypeData: PWSTR(anno_title.as_mut_ptr()),
+        ..Default::default()
+      };
+      if !InsertMenuItemW(self.hmenu, index, true, &info).as_bool() {
+        return Err(os_error!(OsError::IoError(io::Error::last_os_error())));
+      }
+
+      // add our accels
+      if let Some(accelerators) = accelerators {
+        if self.validate_accelerators && !is_accelerator_reachable(&accelerators) {
+          warn!(
+            "Accelerator {:?} of menu item {:?} can't be typed with the current keyboard layout",
+            accelerators, menu_id
+          );
+          self
+            .unreachable_accelerators
+            .push((menu_id, accelerators.clone()));
+        }
+        if let Some(accelerators) = convert_accelerator(menu_id.0, accelerators) {
+          self
+            .state
+            .lock()
+            .unwrap()
+            .accels
+            .insert(menu_id.0, AccelWrapper(accelerators));
+        }
+      }
+      if (CUT_ID..=MINIMIZE_ID).contains(&(menu_id.0 as usize)) {
+        debug!(
+          "Menu item {:?} uses an id reserved for native items, which will be ignored in the same window",
+          menu_id
+        );
+      }
+      self.state.lock().unwrap().ids.insert(menu_id.0);
+      if self.rtl {
+        set_menu_rtl(self.hmenu, true);
+      }
+      Ok(CustomMenuItem(MenuItemAttributes(menu_id.0, self.hmenu)))
+    }
+  }
+
+  // Available only with MenuExtWindows
+  pub fn add_radio_item_group(
+    &mut self,
+    items: &[(MenuId, &str)],
+    menu_type: MenuType,
+  ) -> Vec<CustomMenuItem> {
+    let items: Vec<CustomMenuItem> = items
+      .iter()
+      .map(|(menu_id, title)| {
+        let item = self.add_item(*menu_id, title, None, true, false, menu_type);
+        unsafe {
+          // draw a bullet instead of a checkmark
+          let info = MENUITEMINFOW {
+            cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+            fMask: MIIM_FTYPE,
+            fType: MFT_STRING | MFT_RADIOCHECK,
+            ..Default::default()
+          };
+          SetMenuItemInfoW(self.hmenu, menu_id.0 as u32, false, &info);
+        }
+        item
+      })
+      .collect();
+
+    if let (Some(first), Some(last)) = (items.first(), items.last()) {
+      let bounds = ((first.0).0, (last.0).0);
+      let mut state = self.state.lock().unwrap();
+      for item in &items {
+        state.radio_groups.insert((item.0).0, bounds);
+      }
+    }
+
+    items
+  }
+
+  pub fn add_submenu(&mut self, title: &str, enabled: bool, mut submenu: Menu) -> Submenu {
+    unsafe {
+      self
+        .unreachable_accelerators
+        .append(&mut submenu.unreachable_accelerators);
+      self
+        .mnemonic_conflicts
+        .append(&mut submenu.mnemonic_conflicts);
+      let child_ids = submenu.state.lock().unwrap().ids.clone();
+      self.state.lock().unwrap().ids.extend(child_ids);
+
+      let mut flags = MF_POPUP;
+      if !enabled {
+        flags |= MF_DISABLED;
+      }
+
+      let position = GetMenuItemCount(self.hmenu) as u32;
+      if AppendMenuW(self.hmenu, flags, submenu.hmenu().0 as usize, title).as_bool() {
+        // destroyed along with this menu
+        submenu.release();
+      } else {
+        warn!(
+          "Unable to add submenu {:?}: {}",
+          title,
+          io::Error::last_os_error()
+        );
+      }
+      if self.rtl {
+        set_menu_rtl(self.hmenu, true);
+      }
+
+      Submenu {
+        hmenu: self.hmenu,
+        position,
+      }
+    }
+  }
+
+  pub fn add_native_item(
+    &mut self,
+    item: MenuItem,
+    _menu_type: MenuType,
+  ) -> Option<CustomMenuItem> {
+    match item {
+      MenuItem::Separator => {
+        unsafe {
+          AppendMenuW(self.hmenu, MF_SEPARATOR, 0, PWSTR::default());
+        };
+      }
+      MenuItem::Cut => unsafe {
+        AppendMenuW(self.hmenu, MF_STRING, CUT_ID, "&Cut\tCtrl+X");
       },
       MenuItem::Copy => unsafe {
         AppendMenuW(self.hmenu, MF_STRING, COPY_ID, "&Copy\tCtrl+C");
@@ -259,45 +1580,80 @@ impl Menu {
       },
       // FIXME: create all shortcuts of MenuItem if possible...
       // like linux?
-      _ => (),
+      _ => debug!("Menu item {:?} is not supported on Windows", item),
     };
+    if self.rtl {
+      unsafe { set_menu_rtl(self.hmenu, true) };
+    }
 
     None
   }
 }
 
-/*
-  Disabled as menu's seems to be linked to the app
-  so they are dropped when the app closes.
-  see discussion here;
-
-  https://github.com/tauri-apps/tao/pull/106#issuecomment-880034210
+const MENU_SUBCLASS_ID: usize = 4568;
 
-  impl Drop for Menu {
-    fn drop(&mut self) {
-      unsafe {
-        DestroyMenu(self.hmenu);
-      }
+pub fn initialize(
+  mut menu_builder: Menu,
+  window: HWND,
+  menu_handler: MenuHandler,
+) -> Result<HMENU, MenuError> {
+  // a menu bar can only belong to one window, give a synchronized copy to the others
+  if menu_builder.is_released() {
+    menu_builder = menu_builder.mirror(MenuType::MenuBar);
+  }
+  unsafe {
+    if !SetMenu(window, menu_builder.hmenu()).as_bool() {
+      return Err(MenuError::AttachFailed(os_error!(OsError::IoError(
+        io::Error::last_os_error()
+      ))));
+    }
+    if !DrawMenuBar(window).as_bool() {
+      let error = io::Error::last_os_error();
+      // the menu is still owned by `menu_builder`, which destroys it
+      SetMenu(window, HMENU::default());
+      return Err(MenuError::DrawFailed(os_error!(OsError::IoError(error))));
     }
   }
-*/
 
-const MENU_SUBCLASS_ID: usize = 4568;
+  attach(menu_builder, window, menu_handler)
+}
 
-pub fn initialize(menu_builder: Menu, window: HWND, menu_handler: MenuHandler) -> HMENU {
-  let sender: *mut MenuHandler = Box::into_raw(Box::new(menu_handler));
+// Handle the commands and accelerators of the menu on `window` without making it its menu bar,
+// which only top-level windows can have. The items can then be shown in a popup, or triggered
+// by controls sending `WM_COMMAND` with their id to `window`.
+pub fn attach(
+  menu_builder: Menu,
+  window: HWND,
+  mut menu_handler: MenuHandler,
+) -> Result<HMENU, MenuError> {
   let menu = menu_builder.hmenu();
+  menu_handler.suppress_beep = menu_builder.suppress_beep;
+  menu_handler.edit_item_events = menu_builder.edit_item_events;
+  menu_handler.edit_item_filter = menu_builder.edit_item_filter.clone();
+  menu_handler.window_item_events = menu_builder.window_item_events;
+  menu_handler.set_menu(&menu_builder);
+  // the window keeps using the menu for as long as it exists
+  menu_builder.release();
+  menu_handler.owned_menu = Some(menu);
 
   unsafe {
-    SetWindowSubclass(window, Some(subclass_proc), MENU_SUBCLASS_ID, sender as _);
-    SetMenu(window, menu);
+    let sender: *mut MenuHandler = Box::into_raw(Box::new(menu_handler));
+    if !SetWindowSubclass(window, Some(subclass_proc), MENU_SUBCLASS_ID, sender as _).as_bool() {
+      Box::from_raw(sender);
+      return Err(MenuError::AttachFailed(os_error!(OsError::IoError(
+        io::Error::last_os_error()
+      ))));
+    }
   }
 
   if let Some(accels) = menu_builder.accels() {
     register_accel(window, &accels);
   }
+  MENU_WINDOWS.lock().unwrap().insert(window.0, menu.0);
+  // the icons were sized for the system DPI
+  unsafe { reload_menu_icons(menu) };
 
-  menu
+  Ok(menu)
 }
 
 pub(crate) unsafe extern "system" fn subclass_proc(
@@ -312,42 +1668,212 @@ pub(crate) unsafe extern "system" fn subclass_proc(
   let subclass_input = &*(subclass_input_ptr);
 
   if msg == WM_DESTROY {
+    MENU_WINDOWS.lock().unwrap().remove(&hwnd.0);
+    unregister_accel(hwnd);
+    // the window destroys its menu bar, which must no longer be kept in sync with its mirrors
+    let menu = GetMenu(hwnd);
+    if !menu.is_invalid() {
+      unlink_menu(menu);
+    }
+    // a menu that isn't shown in the menu bar, like the one of a child window, is ours to destroy
+    match subclass_input.owned_menu {
+      Some(owned_menu) if owned_menu != menu => destroy_menu(owned_menu),
+      _ => {}
+    }
     Box::from_raw(subclass_input_ptr);
   }
 
   match msg {
+    win32wm::WM_ENTERMENULOOP => {
+      begin_menu_loop(hwnd);
+      DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+    win32wm::WM_EXITMENULOOP => {
+      end_menu_loop();
+      DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+    win32wm::WM_MENUSELECT => {
+      let flags = util::HIWORD(wparam.0 as u32) as u32;
+      // submenu headers report their position instead of an id, and a null menu
+      // means the menu is being closed.
+      let highlighted = if lparam.0 == 0
+        || flags & MF_POPUP != 0
+        || flags & MF_SEPARATOR != 0
+        || is_section_header(HMENU(lparam.0), util::LOWORD(wparam.0 as u32))
+      {
+        None
+      } else {
+        Some((HMENU(lparam.0), util::LOWORD(wparam.0 as u32)))
+      };
+      MENU_LOOP.with(|state| {
+        if let Some(state) = state.borrow_mut().as_mut() {
+          state.highlighted = highlighted;
+        }
+      });
+
+      let item = if lparam.0 == 0 {
+        None
+      } else if flags & MF_POPUP != 0 {
+        let position = util::LOWORD(wparam.0 as u32) as u32;
+        copy_menu_item(HMENU(lparam.0), position).map(|(info, title)| {
+          HighlightedMenuItem::Submenu {
+            position,
+            title: String::from_utf16_lossy(&title[..info.cch as usize]),
+          }
+        })
+      } else {
+        highlighted.map(|(_, id)| HighlightedMenuItem::Item(MenuId(id)))
+      };
+      let help_text = highlighted.and_then(|(hmenu, id)| {
+        menu_state(hmenu).and_then(|state| state.lock().unwrap().help_texts.get(&id).cloned())
+      });
+      subclass_input.send_event(Event::MenuItemHighlighted {
+        window_id: subclass_input.window_id,
+        item,
+        help_text,
+      });
+      DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+    win32wm::WM_DPICHANGED => {
+      let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+      reload_menu_icons(subclass_input.hmenu);
+      result
+    }
+    win32wm::WM_INITMENUPOPUP => {
+      // mirrors of the popup send the id of the original one
+      let linked = linked_menus(HMENU(wparam.0 as isize));
+      let menu_id = {
+        let will_open_ids = WILL_OPEN_IDS.lock().unwrap();
+        linked
+          .iter()
+          .find_map(|hmenu| will_open_ids.get(&hmenu.0).copied())
+      };
+      if let Some(menu_id) = menu_id {
+        subclass_input.send_event(Event::MenuWillOpen {
+          window_id: subclass_input.window_id,
+          menu_id: MenuId(menu_id),
+        });
+      }
+      DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+    win32wm::WM_MEASUREITEM => {
+      let measure = &mut *(lparam.0 as *mut MEASUREITEMSTRUCT);
+      let item = match measure.CtlType {
+        ODT_MENU => owner_drawn_item(subclass_input.hmenu, measure.itemID as u16),
+        _ => None,
+      };
+      match item {
+        Some(item) => {
+          measure.itemWidth = item.size.width;
+          measure.itemHeight = item.size.height;
+          LRESULT(1)
+        }
+        _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+      }
+    }
+    win32wm::WM_DRAWITEM => {
+      let draw = &*(lparam.0 as *const DRAWITEMSTRUCT);
+      // `hwndItem` is the menu that contains the item
+      let item = match draw.CtlType {
+        ODT_MENU => owner_drawn_item(HMENU(draw.hwndItem.0), draw.itemID as u16)
+          .or_else(|| owner_drawn_item(subclass_input.hmenu, draw.itemID as u16)),
+        _ => None,
+      };
+      match item {
+        Some(item) => {
+          (item.draw)(&MenuItemDrawContext {
+            hdc: draw.hDC.0 as _,
+            left: draw.rcItem.left,
+            top: draw.rcItem.top,
+            right: draw.rcItem.right,
+            bottom: draw.rcItem.bottom,
+            selected: draw.itemState & ODS_SELECTED != 0,
+            disabled: draw.itemState & (ODS_GRAYED | ODS_DISABLED) != 0,
+            checked: draw.itemState & ODS_CHECKED != 0,
+          });
+          LRESULT(1)
+        }
+        _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+      }
+    }
+    // sent when a key matches no mnemonic of the open menu, the default result makes it beep
+    win32wm::WM_MENUCHAR if subclass_input.suppress_beep => {
+      // keep the menu open by selecting the item that is already highlighted, if any
+      match highlighted_position(HMENU(lparam.0)) {
+        Some(position) => LRESULT(((MNC_SELECT as isize) << 16) | position as isize),
+        None => LRESULT((MNC_CLOSE as isize) << 16),
+      }
+    }
     win32wm::WM_COMMAND => {
-      match wparam.0 {
-        CUT_ID => {
+      let menu_id = util::LOWORD(wparam.0 as u32);
+
+      // `HIWORD` is 1 when the command comes from an accelerator
+      if util::HIWORD(wparam.0 as u32) == 1
+        && is_translating_repeat()
+        && !is_repeat_item(subclass_input.hmenu, menu_id)
+      {
+        return LRESULT(0);
+      }
+
+      // the accelerators of `WindowExtWindows::set_accelerators` are handled by the window
+      if util::HIWORD(wparam.0 as u32) == 1
+        && !subclass_input.state.lock().unwrap().ids.contains(&menu_id)
+        && is_window_accel(hwnd, menu_id)
+      {
+        return DefSubclassProc(hwnd, msg, wparam, lparam);
+      }
+
+      let native_id = native_item_for_command(menu_id, &subclass_input.state.lock().unwrap().ids);
+
+      if matches!(native_id, Some(CUT_ID | COPY_ID | PASTE_ID | SELECT_ALL_ID)) {
+        // without a filter or when it declines, the item acts on the focused control
+        let handled_by_app = subclass_input.edit_item_events
+          || subclass_input
+            .edit_item_filter
+            .as_ref()
+            .map_or(false, |filter| (filter.0)(MenuId(menu_id)));
+        if handled_by_app {
+          subclass_input.send_menu_event(menu_id);
+          return LRESULT(0);
+        }
+      }
+      if subclass_input.window_item_events
+        && matches!(native_id, Some(HIDE_ID | CLOSE_ID | QUIT_ID | MINIMIZE_ID))
+      {
+        subclass_input.send_menu_event(menu_id);
+        return LRESULT(0);
+      }
+
+      match native_id {
+        Some(CUT_ID) => {
           execute_edit_command(EditCommand::Cut);
         }
-        COPY_ID => {
+        Some(COPY_ID) => {
           execute_edit_command(EditCommand::Copy);
         }
-        PASTE_ID => {
+        Some(PASTE_ID) => {
           execute_edit_command(EditCommand::Paste);
         }
-        SELECT_ALL_ID => {
+        Some(SELECT_ALL_ID) => {
           execute_edit_command(EditCommand::SelectAll);
         }
-        HIDE_ID => {
+        Some(HIDE_ID) => {
           ShowWindow(hwnd, SW_HIDE);
         }
-        CLOSE_ID => {
+        Some(CLOSE_ID) => {
           subclass_input.send_event(Event::WindowEvent {
             window_id: RootWindowId(WindowId(hwnd.0)),
             event: WindowEvent::CloseRequested,
           });
         }
-        QUIT_ID => {
+        Some(QUIT_ID) => {
           subclass_input.send_event(Event::LoopDestroyed);
         }
-        MINIMIZE_ID => {
+        Some(MINIMIZE_ID) => {
           ShowWindow(hwnd, SW_MINIMIZE);
         }
         _ => {
-          let menu_id = util::LOWORD(wparam.0 as u32);
-          if MENU_IDS.lock().unwrap().contains(&menu_id) {
+          if subclass_input.state.lock().unwrap().ids.contains(&menu_id) {
             subclass_input.send_menu_event(menu_id);
           }
         }
@@ -358,13 +1884,177 @@ pub(crate) unsafe extern "system" fn subclass_proc(
   }
 }
 
+// The reserved id of the native item triggered by the command `id`. Custom items take
+// precedence, so a `MenuId` that happens to collide with a reserved id still sends its event.
+fn native_item_for_command(id: u16, menu_ids: &HashSet<u16>) -> Option<usize> {
+  if menu_ids.contains(&id) {
+    return None;
+  }
+  match id as usize {
+    id @ CUT_ID..=MINIMIZE_ID => Some(id),
+    _ => None,
+  }
+}
+
+// Id of the `MenuEvent` sent for a native item when `MenuExtWindows::with_edit_item_events` or
+// `MenuExtWindows::with_window_item_events` is enabled.
+pub fn native_item_id(item: &MenuItem) -> Option<MenuId> {
+  let id = match item {
+    MenuItem::Cut => CUT_ID,
+    MenuItem::Copy => COPY_ID,
+    MenuItem::Paste => PASTE_ID,
+    MenuItem::SelectAll => SELECT_ALL_ID,
+    MenuItem::Hide => HIDE_ID,
+    MenuItem::CloseWindow => CLOSE_ID,
+    MenuItem::Quit => QUIT_ID,
+    MenuItem::Minimize => MINIMIZE_ID,
+    _ => return None,
+  };
+  Some(MenuId(id as u16))
+}
+
+// Install a message filter for the duration of the menu loop so we can intercept clicks on
+// items that should not dismiss the menu.
+unsafe fn begin_menu_loop(owner: HWND) {
+  let hook = SetWindowsHookExW(
+    WH_MSGFILTER,
+    Some(menu_msg_filter_proc),
+    HINSTANCE::default(),
+    GetCurrentThreadId(),
+  );
+  MENU_LOOP.with(|state| {
+    if let Some(previous) = state.borrow_mut().replace(MenuLoopState {
+      hook,
+      owner,
+      highlighted: None,
+    }) {
+      UnhookWindowsHookEx(previous.hook);
+    }
+  });
+}
+
+unsafe fn end_menu_loop() {
+  MENU_LOOP.with(|state| {
+    if let Some(state) = state.borrow_mut().take() {
+      UnhookWindowsHookEx(state.hook);
+    }
+  });
+}
+
+unsafe extern "system" fn menu_msg_filter_proc(
+  code: i32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+) -> LRESULT {
+  if code == MSGF_MENU as i32 {
+    let msg = &*(lparam.0 as *const MSG);
+    let activated = match msg.message {
+      win32wm::WM_LBUTTONUP => true,
+      win32wm::WM_KEYDOWN => msg.wParam.0 == VK_RETURN as usize,
+      _ => false,
+    };
+    if activated && toggle_keep_open_item(msg) {
+      // swallow the message so the menu doesn't close
+      return LRESULT(1);
+    }
+  }
+
+  let hook = MENU_LOOP.with(|state| state.borrow().as_ref().map(|s| s.hook));
+  CallNextHookEx(hook.unwrap_or_default(), code, wparam, lparam)
+}
+
+// Toggle the highlighted item in place if it was flagged with `set_keep_open_on_click`.
+// Returns `true` if the activation was handled.
+unsafe fn toggle_keep_open_item(msg: &MSG) -> bool {
+  let (owner, hmenu, id) = match MENU_LOOP.with(|state| {
+    state
+      .borrow()
+      .as_ref()
+      .and_then(|s| s.highlighted.map(|(hmenu, id)| (s.owner, hmenu, id)))
+  }) {
+    Some(highlighted) => highlighted,
+    None => return false,
+  };
+
+  if !is_keep_open_item(hmenu, id) {
+    return false;
+  }
+
+  // the button may have been released outside of the highlighted item
+  if msg.message == WM_LBUTTONUP {
+    let position = MenuItemFromPoint(HWND::default(), hmenu, msg.pt);
+    if position < 0 || GetMenuItemID(hmenu, position) != id as u32 {
+      return false;
+    }
+  }
+
+  let state = GetMenuState(hmenu, id as u32, MF_BYCOMMAND);
+  if state & (MF_GRAYED | MF_DISABLED) != 0 {
+    return false;
+  }
+
+  let check = if state & MF_CHECKED != 0 {
+    MF_UNCHECKED
+  } else {
+    MF_CHECKED
+  };
+  for hmenu in linked_menus(hmenu) {
+    CheckMenuItem(hmenu, id as u32, check);
+  }
+
+  // notify the app exactly like a regular click would
+  SendMessageW(owner, WM_COMMAND, WPARAM(id as usize), LPARAM(0));
+
+  // keyboard messages are not targeted at the popup window, so look up the top-most one
+  let menu_window = if msg.message == WM_LBUTTONUP {
+    msg.hwnd
+  } else {
+    FindWindowW("#32768", PWSTR::default())
+  };
+  InvalidateRect(menu_window, ptr::null(), false);
+
+  true
+}
+
+// Whether the accelerator of the item `id`, in `hmenu` or its submenus, fires again while its keys
+// are held down.
+unsafe fn is_repeat_item(hmenu: HMENU, id: u16) -> bool {
+  find_item_state(hmenu, id).map_or(true, |state| !state.lock().unwrap().no_repeat.contains(&id))
+}
+
+// Whether the item `id` of `hmenu` was flagged with `set_keep_open_on_click`.
+fn is_keep_open_item(hmenu: HMENU, id: u16) -> bool {
+  menu_state(hmenu).map_or(false, |state| state.lock().unwrap().keep_open.contains(&id))
+}
+
 enum EditCommand {
   Copy,
   Cut,
   Paste,
   SelectAll,
 }
+
+// `EM_SETSEL`, to select the whole content of an edit control.
+const EM_SETSEL: u32 = 0x00B1;
+
 fn execute_edit_command(command: EditCommand) {
+  unsafe {
+    // standard edit controls handle the clipboard messages themselves, which doesn't depend
+    // on their key bindings nor on the modifiers currently held by the user
+    let focus = GetFocus();
+    if is_edit_control(focus) {
+      let (msg, wparam, lparam) = match command {
+        EditCommand::Copy => (WM_COPY, 0, 0),
+        EditCommand::Cut => (WM_CUT, 0, 0),
+        EditCommand::Paste => (WM_PASTE, 0, 0),
+        EditCommand::SelectAll => (EM_SETSEL, 0, -1),
+      };
+      SendMessageW(focus, msg, WPARAM(wparam), LPARAM(lparam));
+      return;
+    }
+  }
+
+  // other controls, like web views, only respond to the keyboard shortcuts
   let key = match command {
     EditCommand::Copy => 0x43,      // c
     EditCommand::Cut => 0x58,       // x
@@ -396,8 +2086,19 @@ fn execute_edit_command(command: EditCommand) {
   }
 }
 
+// Whether `hwnd` is a standard or rich edit control.
+unsafe fn is_edit_control(hwnd: HWND) -> bool {
+  if hwnd.is_invalid() {
+    return false;
+  }
+  let mut class_name = [0u16; 64];
+  let len = GetClassNameW(hwnd, PWSTR(class_name.as_mut_ptr()), class_name.len() as _);
+  let class_name = String::from_utf16_lossy(&class_name[..len.max(0) as usize]).to_lowercase();
+  class_name == "edit" || class_name.starts_with("richedit")
+}
+
 // Convert a hotkey to an accelerator.
-fn convert_accelerator(id: u16, key: Accelerator) -> Option<ACCEL> {
+pub(crate) fn convert_accelerator(id: u16, key: Accelerator) -> Option<ACCEL> {
   let mut virt_key = FVIRTKEY;
   let key_mods: ModifiersState = key.mods;
   if key_mods.control_key() {
@@ -434,81 +2135,824 @@ fn convert_accelerator(id: u16, key: Accelerator) -> Option<ACCEL> {
   })
 }
 
-// Format the hotkey in a Windows-native way.
-fn format_hotkey(key: Accelerator, s: &mut String) {
-  let key_mods: ModifiersState = key.mods;
-  if key_mods.control_key() {
-    s.push_str("Ctrl+");
+// The letter following the first single `&` of `title`, lowercased. `&&` is a literal `&`, and
+// the accelerator text after a tab is ignored.
+fn mnemonic(title: &str) -> Option<char> {
+  let mut chars = title.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\t' => break,
+      '&' => match chars.next() {
+        Some('&') => continue,
+        Some(mnemonic) => return mnemonic.to_lowercase().next(),
+        None => break,
+      },
+      _ => {}
+    }
   }
-  if key_mods.shift_key() {
-    s.push_str("Shift+");
+  None
+}
+
+// Loads the `.ico` buffer `icon` into a bitmap of the given size, to display in a menu.
+unsafe fn load_menu_bitmap(icon: &[u8], width: i32, height: i32) -> Result<HBITMAP, RootOsError> {
+  let hicon = util::get_hicon_from_buffer(icon, width, height).ok_or_else(|| {
+    os_error!(OsError::CreationError(
+      "Unable to load the icon from the buffer"
+    ))
+  })?;
+  let hbitmap = util::get_hbitmap_from_hicon(hicon, width, height);
+  DestroyIcon(hicon);
+  hbitmap.ok_or_else(|| {
+    os_error!(OsError::CreationError(
+      "Unable to convert the icon to a bitmap"
+    ))
+  })
+}
+
+// The small icon size for the items of `hmenu`. Its bitmaps are shared by its mirrors, so this
+// is the size at the highest DPI of the windows it is attached to, or at the system DPI if it
+// isn't attached yet.
+unsafe fn menu_icon_size(hmenu: HMENU) -> (i32, i32) {
+  let dpi = menu_windows(hmenu)
+    .into_iter()
+    .map(|(hwnd, _)| hwnd_dpi(hwnd))
+    .max();
+  match dpi {
+    Some(dpi) => (
+      system_metrics_for_dpi(SM_CXSMICON, dpi),
+      system_metrics_for_dpi(SM_CYSMICON, dpi),
+    ),
+    None => (GetSystemMetrics(SM_CXSMICON), GetSystemMetrics(SM_CYSMICON)),
   }
-  if key_mods.alt_key() {
-    s.push_str("Alt+");
-  }
-  if key_mods.super_key() {
-    s.push_str("Windows+");
-  }
-  match &key.key {
-    KeyCode::KeyA => s.push('A'),
-    KeyCode::KeyB => s.push('B'),
-    KeyCode::KeyC => s.push('C'),
-    KeyCode::KeyD => s.push('D'),
-    KeyCode::KeyE => s.push('E'),
-    KeyCode::KeyF => s.push('F'),
-    KeyCode::KeyG => s.push('G'),
-    KeyCode::KeyH => s.push('H'),
-    KeyCode::KeyI => s.push('I'),
-    KeyCode::KeyJ => s.push('J'),
-    KeyCode::KeyK => s.push('K'),
-    KeyCode::KeyL => s.push('L'),
-    KeyCode::KeyM => s.push('M'),
-    KeyCode::KeyN => s.push('N'),
-    KeyCode::KeyO => s.push('O'),
-    KeyCode::KeyP => s.push('P'),
-    KeyCode::KeyQ => s.push('Q'),
-    KeyCode::KeyR => s.push('R'),
-    KeyCode::KeyS => s.push('S'),
-    KeyCode::KeyT => s.push('T'),
-    KeyCode::KeyU => s.push('U'),
-    KeyCode::KeyV => s.push('V'),
-    KeyCode::KeyW => s.push('W'),
-    KeyCode::KeyX => s.push('X'),
-    KeyCode::KeyY => s.push('Y'),
-    KeyCode::KeyZ => s.push('Z'),
-    KeyCode::Digit0 => s.push('0'),
-    KeyCode::Digit1 => s.push('1'),
-    KeyCode::Digit2 => s.push('2'),
-    KeyCode::Digit3 => s.push('3'),
-    KeyCode::Digit4 => s.push('4'),
-    KeyCode::Digit5 => s.push('5'),
-    KeyCode::Digit6 => s.push('6'),
-    KeyCode::Digit7 => s.push('7'),
-    KeyCode::Digit8 => s.push('8'),
-    KeyCode::Digit9 => s.push('9'),
-    KeyCode::Comma => s.push(','),
-    KeyCode::Minus => s.push('-'),
-    KeyCode::Period => s.push('.'),
-    KeyCode::Space => s.push_str("Space"),
-    KeyCode::Equal => s.push('='),
-    KeyCode::Semicolon => s.push(';'),
-    KeyCode::Slash => s.push('/'),
-    KeyCode::Backslash => s.push('\\'),
-    KeyCode::Quote => s.push('\''),
-    KeyCode::Backquote => s.push('`'),
-    KeyCode::BracketLeft => s.push('['),
-    KeyCode::BracketRight => s.push(']'),
-    KeyCode::Tab => s.push_str("Tab"),
-    KeyCode::Escape => s.push_str("Esc"),
-    KeyCode::Delete => s.push_str("Del"),
-    KeyCode::Insert => s.push_str("Ins"),
-    KeyCode::PageUp => s.push_str("PgUp"),
-    KeyCode::PageDown => s.push_str("PgDn"),
-    // These names match LibreOffice.
-    KeyCode::ArrowLeft => s.push_str("Left"),
-    KeyCode::ArrowRight => s.push_str("Right"),
-    KeyCode::ArrowUp => s.push_str("Up"),
-    KeyCode::ArrowDown => s.push_str("Down"),
-    _ => s.push_str(&format!("{:?}", key.key)),
+}
+
+// Loads the icons of the items of `hmenu` and its submenus again, at the DPI of the windows they
+// are attached to.
+unsafe fn reload_menu_icons(hmenu: HMENU) {
+  let icons = menu_state(hmenu)
+    .map(|state| state.lock().unwrap().icons.clone())
+    .unwrap_or_default();
+  for (id, icon) in icons {
+    if let Err(error) = MenuItemAttributes(id, hmenu).set_icon(icon) {
+      warn!("Unable to reload the icon of menu item {}: {}", id, error);
+    }
+  }
+  for position in 0..GetMenuItemCount(hmenu).max(0) {
+    let submenu = GetSubMenu(hmenu, position);
+    if !submenu.is_invalid() {
+      reload_menu_icons(submenu);
+    }
+  }
+}
+
+// Doubles every `&` so the title is displayed literally instead of marking a mnemonic.
+fn escape_mnemonics(title: &str) -> String {
+  title.replace('&', "&&")
+}
+
+// Register the accelerator table again on the windows whose menu contains `hmenu`.
+fn update_window_accels(hmenu: HMENU) {
+  for (hwnd, menu) in menu_windows(hmenu) {
+    match menu_accels(menu) {
+      Some(accels) => register_accel(hwnd, &accels),
+      None => unregister_accel(hwnd),
+    }
+  }
+}
+
+// The windows whose menu is `hmenu`, one of its mirrors, or contains one of them, along with
+// their menu.
+fn menu_windows(hmenu: HMENU) -> Vec<(HWND, HMENU)> {
+  let linked = linked_menus(hmenu);
+  MENU_WINDOWS
+    .lock()
+    .unwrap()
+    .iter()
+    .filter(|(_, menu)| unsafe { menu_contains(HMENU(**menu), &linked) })
+    .map(|(hwnd, menu)| (HWND(*hwnd), HMENU(*menu)))
+    .collect()
+}
+
+// Whether `hmenu` or one of its submenus is one of `menus`.
+unsafe fn menu_contains(hmenu: HMENU, menus: &[HMENU]) -> bool {
+  menus.contains(&hmenu)
+    || (0..GetMenuItemCount(hmenu).max(0)).any(|position| {
+      let submenu = GetSubMenu(hmenu, position);
+      !submenu.is_invalid() && menu_contains(submenu, menus)
+    })
+}
+
+// The accelerators of the visible items of `hmenu` and its submenus, if any.
+fn menu_accels(hmenu: HMENU) -> Option<Vec<ACCEL>> {
+  let mut accels = Vec::new();
+  unsafe { collect_menu_accels(hmenu, &mut accels) };
+  if accels.is_empty() {
+    None
+  } else {
+    Some(accels)
+  }
+}
+
+unsafe fn collect_menu_accels(hmenu: HMENU, accels: &mut Vec<ACCEL>) {
+  // only the items hidden from this menu, another one may show an item with the same id
+  let hidden_ids: HashSet<u16> = HIDDEN_ITEMS
+    .lock()
+    .unwrap()
+    .get(&hmenu.0)
+    .into_iter()
+    .flatten()
+    .map(|item| item.id)
+    .collect();
+  if let Some(state) = menu_state(hmenu) {
+    accels.extend(
+      state
+        .lock()
+        .unwrap()
+        .accels
+        .iter()
+        .filter(|(id, _)| !hidden_ids.contains(id))
+        .map(|(_, accel)| accel.0),
+    );
+  }
+  for position in 0..GetMenuItemCount(hmenu).max(0) {
+    let submenu = GetSubMenu(hmenu, position);
+    if !submenu.is_invalid() {
+      collect_menu_accels(submenu, accels);
+    }
   }
 }
+
+// The title of the item `id`, including its accelerator text.
+unsafe fn menu_item_title(hmenu: HMENU, id: u16) -> Option<String> {
+  let mut info = MENUITEMINFOW {
+    cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+    fMask: MIIM_STRING,
+    ..Default::default()
+  };
+  // the first call only retrieves the length of the title
+  if !GetMenuItemInfoW(hmenu, id as u32, false, &mut info).as_bool() {
+    return None;
+  }
+  let mut title = vec![0u16; info.cch as usize + 1];
+  info.cch += 1;
+  info.dwTypeData = PWSTR(title.as_mut_ptr());
+  if !GetMenuItemInfoW(hmenu, id as u32, false, &mut info).as_bool() {
+    return None;
+  }
+  Some(String::from_utf16_lossy(&title[..info.cch as usize]))
+}
+
+unsafe fn get_menu_item_info(hmenu: HMENU, position: u32) -> Option<MENUITEMINFOW> {
+  let mut info = MENUITEMINFOW {
+    cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+    fMask: MIIM_FTYPE | MIIM_ID | MIIM_DATA | MIIM_SUBMENU,
+    ..Default::default()
+  };
+  if GetMenuItemInfoW(hmenu, position, true, &mut info).as_bool() {
+    Some(info)
+  } else {
+    None
+  }
+}
+
+unsafe fn set_menu_item_data(hmenu: HMENU, id: u16, data: usize) {
+  let info = MENUITEMINFOW {
+    cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+    fMask: MIIM_DATA,
+    dwItemData: data,
+    ..Default::default()
+  };
+  SetMenuItemInfoW(hmenu, id as u32, false, &info);
+}
+
+// Select `id` and deselect the other items of its radio group, delimited by the position of
+// `first` and `last` so items of the menu outside the group are left untouched.
+unsafe fn check_radio_item(hmenu: HMENU, first: u16, last: u16, id: u16) {
+  let position_of = |id: u16| {
+    (0..GetMenuItemCount(hmenu).max(0))
+      .find(|position| GetMenuItemID(hmenu, *position) == id as u32)
+  };
+  if let (Some(first), Some(last), Some(position)) =
+    (position_of(first), position_of(last), position_of(id))
+  {
+    CheckMenuRadioItem(
+      hmenu,
+      first as u32,
+      last as u32,
+      position as u32,
+      MF_BYPOSITION,
+    );
+  }
+}
+
+// Position of the item of `hmenu` that is currently highlighted.
+unsafe fn highlighted_position(hmenu: HMENU) -> Option<u32> {
+  (0..GetMenuItemCount(hmenu).max(0) as u32)
+    .find(|position| GetMenuState(hmenu, *position, MF_BYPOSITION) & MF_HILITE != 0)
+}
+
+// Whether the item `id` of `hmenu`, as reported by `WM_MENUSELECT`, is a section header. Headers
+// have no command id, so unless an item of the menu uses `MenuId(0)` too, it is one of them.
+unsafe fn is_section_header(hmenu: HMENU, id: u16) -> bool {
+  let mut items = (0..GetMenuItemCount(hmenu).max(0) as u32)
+    .filter_map(|position| get_menu_item_info(hmenu, position))
+    .filter(|info| {
+      id == 0 && info.wID == 0 && info.hSubMenu.is_invalid() && info.fType & MFT_SEPARATOR == 0
+    })
+    .peekable();
+  items.peek().is_some() && items.all(|info| info.dwItemData == SECTION_HEADER_DATA)
+}
+
+// Move the highlight to the next (or previous) enabled item, wrapping around at the ends.
+unsafe fn move_highlight(window: HWND, hmenu: HMENU, forward: bool) {
+  let count = GetMenuItemCount(hmenu).max(0) as u32;
+  if count == 0 {
+    return;
+  }
+
+  let current = highlighted_position(hmenu);
+  // start right before the first item (or after the last one) when nothing is highlighted yet
+  let start = current.unwrap_or(if forward { count - 1 } else { 0 });
+  let candidate = (1..=count)
+    .map(|offset| {
+      if forward {
+        (start + offset) % count
+      } else {
+        (start + count - offset) % count
+      }
+    })
+    .find(|position| {
+      // section headers are disabled too
+      let state = GetMenuState(hmenu, *position, MF_BYPOSITION);
+      state & (MF_GRAYED | MF_DISABLED | MF_SEPARATOR) == 0
+    });
+
+  if let Some(position) = candidate {
+    if let Some(current) = current {
+      HiliteMenuItem(window, hmenu, current, MF_BYPOSITION | MF_UNHILITE);
+    }
+    HiliteMenuItem(window, hmenu, position, MF_BYPOSITION | MF_HILITE);
+  }
+}
+
+// Lays out the items of `hmenu` and its submenus from right to left, or back to left to right.
+// In a menu bar, right-justifying the first item moves all of them to the right edge.
+unsafe fn set_menu_rtl(hmenu: HMENU, rtl: bool) {
+  for position in 0..GetMenuItemCount(hmenu).max(0) as u32 {
+    let mut info = MENUITEMINFOW {
+      cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+      fMask: MIIM_FTYPE | MIIM_SUBMENU,
+      ..Default::default()
+    };
+    if !GetMenuItemInfoW(hmenu, position, true, &mut info).as_bool() {
+      continue;
+    }
+    info.fType &= !(MFT_RIGHTORDER | MFT_RIGHTJUSTIFY);
+    if rtl {
+      info.fType |= MFT_RIGHTORDER;
+      if position == 0 {
+        info.fType |= MFT_RIGHTJUSTIFY;
+      }
+    }
+    info.fMask = MIIM_FTYPE;
+    SetMenuItemInfoW(hmenu, position, true, &info);
+
+    if !info.hSubMenu.is_invalid() {
+      set_menu_rtl(info.hSubMenu, rtl);
+    }
+  }
+}
+
+// Everything needed to insert the item at `position` again, `dwTypeData` pointing to the title.
+unsafe fn copy_menu_item(hmenu: HMENU, position: u32) -> Option<(MENUITEMINFOW, Vec<u16>)> {
+  let mut info = MENUITEMINFOW {
+    cbSize: std::mem::size_of::<MENUITEMINFOW>() as _,
+    fMask: MIIM_FTYPE
+      | MIIM_ID
+      | MIIM_STATE
+      | MIIM_DATA
+      | MIIM_SUBMENU
+      | MIIM_STRING
+      | MIIM_BITMAP
+      | MIIM_CHECKMARKS,
+    ..Default::default()
+  };
+  // the first call only retrieves the length of the title
+  if !GetMenuItemInfoW(hmenu, position, true, &mut info).as_bool() {
+    return None;
+  }
+  let mut title = vec![0u16; info.cch as usize + 1];
+  if info.cch > 0 {
+    info.cch += 1;
+    info.dwTypeData = PWSTR(title.as_mut_ptr());
+    GetMenuItemInfoW(hmenu, position, true, &mut info);
+  }
+  Some((info, title))
+}
+
+// Remove the item `id` from `hmenu`, remembering where to insert it back.
+unsafe fn hide_menu_item(hmenu: HMENU, id: u16) {
+  let mut hidden_items = HIDDEN_ITEMS.lock().unwrap();
+  let hidden = hidden_items.entry(hmenu.0).or_default();
+  if hidden.iter().any(|item| item.id == id) {
+    return;
+  }
+  let position = match (0..GetMenuItemCount(hmenu).max(0) as u32)
+    .find(|position| GetMenuItemID(hmenu, *position as i32) == id as u32)
+  {
+    Some(position) => position,
+    None => return,
+  };
+  let (info, title) = match copy_menu_item(hmenu, position) {
+    Some(item) => item,
+    None => return,
+  };
+
+  // the position among all the items, hidden ones included, so showing the items in any order
+  // restores the original layout
+  let mut slot = 0;
+  let mut visible = 0;
+  loop {
+    if !hidden.iter().any(|item| item.slot == slot) {
+      if visible == position {
+        break;
+      }
+      visible += 1;
+    }
+    slot += 1;
+  }
+
+  if RemoveMenu(hmenu, position, MF_BYPOSITION).as_bool() {
+    hidden.push(HiddenItem {
+      id,
+      slot,
+      info,
+      title,
+    });
+  }
+}
+
+// Insert the item `id` hidden by `hide_menu_item` back into `hmenu`.
+unsafe fn show_menu_item(hmenu: HMENU, id: u16) {
+  let mut hidden_items = HIDDEN_ITEMS.lock().unwrap();
+  let hidden = match hidden_items.get_mut(&hmenu.0) {
+    Some(hidden) => hidden,
+    None => return,
+  };
+  let mut item = match hidden.iter().position(|item| item.id == id) {
+    Some(index) => hidden.remove(index),
+    None => return,
+  };
+  let position = item.slot - hidden.iter().filter(|other| other.slot < item.slot).count() as u32;
+  if !item.info.dwTypeData.is_null() {
+    item.info.dwTypeData = PWSTR(item.title.as_mut_ptr());
+  }
+  InsertMenuItemW(hmenu, position, true, &item.info);
+  if hidden.is_empty() {
+    hidden_items.remove(&hmenu.0);
+  }
+}
+
+// Recreate `hmenu` and its submenus. `linked` copies are linked to their original so item state
+// changes are applied to both.
+unsafe fn copy_hmenu(hmenu: HMENU, popup: bool, linked: bool) -> HMENU {
+  let copy = if popup {
+    CreatePopupMenu()
+  } else {
+    CreateMenu()
+  };
+  if linked {
+    link_menus(hmenu, copy);
+  }
+
+  for position in 0..GetMenuItemCount(hmenu).max(0) as u32 {
+    let (mut info, _title) = match copy_menu_item(hmenu, position) {
+      Some(item) => item,
+      None => continue,
+    };
+    if !info.hSubMenu.is_invalid() {
+      info.hSubMenu = copy_hmenu(info.hSubMenu, true, linked);
+    }
+    InsertMenuItemW(copy, position, true, &info);
+  }
+
+  // mirrors share the state of their original, duplicates start with a copy of it
+  if let Some(state) = menu_state(hmenu) {
+    let state = match linked {
+      true => state,
+      false => Arc::new(Mutex::new(state.lock().unwrap().duplicate(copy))),
+    };
+    MENU_STATES.lock().unwrap().insert(copy.0, state);
+  }
+
+  copy
+}
+
+fn link_menus(original: HMENU, mirror: HMENU) {
+  let mut mirrors = MENU_MIRRORS.lock().unwrap();
+  let mut linked = mirrors
+    .get(&original.0)
+    .cloned()
+    .unwrap_or_else(|| vec![original.0]);
+  linked.push(mirror.0);
+  for hmenu in &linked {
+    mirrors.insert(*hmenu, linked.clone());
+  }
+}
+
+// Destroys `hmenu` and its submenus, which are no longer kept in sync with their mirrors.
+pub(crate) unsafe fn destroy_menu(hmenu: HMENU) {
+  unlink_menu(hmenu);
+  DestroyMenu(hmenu);
+}
+
+unsafe fn unlink_menu(hmenu: HMENU) {
+  for position in 0..GetMenuItemCount(hmenu).max(0) {
+    let submenu = GetSubMenu(hmenu, position);
+    if !submenu.is_invalid() {
+      unlink_menu(submenu);
+    }
+  }
+
+  HIDDEN_ITEMS.lock().unwrap().remove(&hmenu.0);
+  WILL_OPEN_IDS.lock().unwrap().remove(&hmenu.0);
+  let state = MENU_STATES.lock().unwrap().remove(&hmenu.0);
+
+  let mut last_linked = true;
+  let mut mirrors = MENU_MIRRORS.lock().unwrap();
+  if let Some(linked) = mirrors.remove(&hmenu.0) {
+    let remaining: Vec<isize> = linked.into_iter().filter(|h| *h != hmenu.0).collect();
+    for h in &remaining {
+      if remaining.len() > 1 {
+        mirrors.insert(*h, remaining.clone());
+      } else {
+        mirrors.remove(h);
+      }
+    }
+    last_linked = remaining.is_empty();
+  }
+  drop(mirrors);
+
+  // the mirrors still display the bitmaps of their shared state
+  if let (Some(state), true) = (state, last_linked) {
+    state.lock().unwrap().delete_bitmaps();
+  }
+}
+
+// A copy of `hbitmap` that can be freed independently.
+unsafe fn copy_bitmap(hbitmap: isize) -> isize {
+  CopyImage(HANDLE(hbitmap), IMAGE_BITMAP, 0, 0, LR_CREATEDIBSECTION).0
+}
+
+// The state of the menu owning the item `id`, among `hmenu` and its submenus. Items added with
+// `Menu::add_accelerator_only` only have an accelerator.
+unsafe fn find_item_state(hmenu: HMENU, id: u16) -> Option<SharedMenuState> {
+  let state = menu_state(hmenu);
+  if let Some(state) = &state {
+    if state.lock().unwrap().accels.contains_key(&id) {
+      return Some(state.clone());
+    }
+  }
+  for position in 0..GetMenuItemCount(hmenu).max(0) {
+    let submenu = GetSubMenu(hmenu, position);
+    if submenu.is_invalid() {
+      if GetMenuItemID(hmenu, position) == id as u32 {
+        return state;
+      }
+    } else if let Some(state) = find_item_state(submenu, id) {
+      return Some(state);
+    }
+  }
+  None
+}
+
+// The owner-drawn item `id` of `hmenu` or its submenus. The lock of its state is released before
+// returning, so the callback can change the menu.
+unsafe fn owner_drawn_item(hmenu: HMENU, id: u16) -> Option<OwnerDrawnItem> {
+  let state = menu_state(hmenu)
+    .filter(|state| state.lock().unwrap().owner_drawn.contains_key(&id))
+    .or_else(|| find_item_state(hmenu, id))?;
+  let item = state.lock().unwrap().owner_drawn.get(&id).cloned();
+  item
+}
+
+// The state of `hmenu`, shared with its mirrors.
+fn menu_state(hmenu: HMENU) -> Option<SharedMenuState> {
+  MENU_STATES.lock().unwrap().get(&hmenu.0).cloned()
+}
+
+// All the handles kept in sync with `hmenu`, including `hmenu` itself.
+fn linked_menus(hmenu: HMENU) -> Vec<HMENU> {
+  MENU_MIRRORS
+    .lock()
+    .unwrap()
+    .get(&hmenu.0)
+    .map(|linked| linked.iter().map(|hmenu| HMENU(*hmenu)).collect())
+    .unwrap_or_else(|| vec![hmenu])
+}
+
+// Whether the items of a menu with the `accelerator_text` setting display their accelerator.
+fn is_accelerator_text_shown(accelerator_text: AcceleratorText) -> bool {
+  match accelerator_text {
+    AcceleratorText::Show => true,
+    AcceleratorText::Hide => false,
+    AcceleratorText::System => keyboard_preferred(),
+  }
+}
+
+// Whether the keyboard preference accessibility setting is on, asking applications to display
+// the keyboard interfaces they would otherwise hide.
+fn keyboard_preferred() -> bool {
+  let mut preferred = BOOL::default();
+  let ok =
+    unsafe { SystemParametersInfoW(SPI_GETKEYBOARDPREF, 0, &mut preferred as *mut _ as _, 0) };
+  ok.as_bool() && preferred.as_bool()
+}
+
+// Check that the accelerator key can be typed on the active keyboard layout,
+// without requiring AltGr (which Windows reports as Ctrl+Alt).
+fn is_accelerator_reachable(key: &Accelerator) -> bool {
+  let vk_code = match key_to_vk(&key.key) {
+    Some(vk_code) => vk_code,
+    None => return false,
+  };
+  // `VkKeyScanW` returns -1 when the character has no key on this layout
+  if vk_code == VIRTUAL_KEY::MAX {
+    return false;
+  }
+  let mod_code = vk_code >> 8;
+  if mod_code & 0x02 != 0 && mod_code & 0x04 != 0 {
+    return false;
+  }
+  unsafe { MapVirtualKeyW((vk_code & 0x00ff) as u32, MAPVK_VK_TO_VSC) != 0 }
+}
+
+#[test]
+fn test_custom_item_with_reserved_id() {
+  let mut menu = Menu::new_popup_menu();
+  menu.add_native_item(MenuItem::Minimize, MenuType::ContextMenu);
+
+  // native items keep working when no custom item collides with them
+  assert_eq!(
+    native_item_for_command(CUT_ID as u16, &menu.state.lock().unwrap().ids),
+    Some(CUT_ID)
+  );
+
+  // the custom item receives the command, and its event, instead of the native one
+  menu.add_item(
+    MenuId(5001),
+    "Custom",
+    None,
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+  let ids = menu.state.lock().unwrap().ids.clone();
+  assert_eq!(CUT_ID, 5001);
+  assert_eq!(native_item_for_command(5001, &ids), None);
+  assert_eq!(
+    native_item_for_command(MINIMIZE_ID as u16, &ids),
+    Some(MINIMIZE_ID)
+  );
+}
+
+#[test]
+fn test_detached_menus_are_destroyed() {
+  use windows::Win32::System::Threading::{GetCurrentProcess, GetGuiResources, GR_USEROBJECTS};
+
+  let user_objects = || unsafe { GetGuiResources(GetCurrentProcess(), GR_USEROBJECTS) };
+  let before = user_objects();
+
+  for _ in 0..5000 {
+    let mut submenu = Menu::new_popup_menu();
+    submenu.add_item(MenuId(1), "Item", None, true, false, MenuType::MenuBar);
+    let mut menu = Menu::new();
+    menu.add_submenu("Submenu", true, submenu);
+    let _mirror = menu.mirror(MenuType::MenuBar);
+  }
+
+  assert!(user_objects() < before + 100);
+}
+
+#[test]
+fn test_mnemonic() {
+  assert_eq!(mnemonic("&Cut\tCtrl+X"), Some('c'));
+  assert_eq!(mnemonic("Save &As..."), Some('a'));
+  assert_eq!(mnemonic("Fish && &Chips"), Some('c'));
+  assert_eq!(mnemonic("Fish && Chips"), None);
+  assert_eq!(mnemonic("Trailing &"), None);
+  assert_eq!(mnemonic("Quit\t&Q"), None);
+}
+
+#[test]
+fn test_escape_mnemonics() {
+  assert_eq!(escape_mnemonics("Save & Exit"), "Save && Exit");
+  assert_eq!(escape_mnemonics("R&D & Q&A"), "R&&D && Q&&A");
+  assert_eq!(mnemonic(&escape_mnemonics("R&D & Q&A")), None);
+
+  let mut menu = Menu::new_popup_menu();
+  let item = menu.add_item_escaped(
+    MenuId(700),
+    "R&D & Q&A",
+    None,
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+  let title = unsafe { menu_item_title(menu.hmenu(), item.0.id().0) }.unwrap();
+  assert_eq!(title, "R&&D && Q&&A");
+}
+
+#[test]
+fn test_items() {
+  let mut menu = Menu::new_popup_menu();
+  let accel = Accelerator::new(ModifiersState::CONTROL, crate::keyboard::KeyCode::KeyO);
+  menu.add_item(
+    MenuId(701),
+    "Open",
+    Some(accel),
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+  menu.add_native_item(MenuItem::Separator, MenuType::ContextMenu);
+  menu.add_submenu("Recent", true, Menu::new_popup_menu());
+
+  assert_eq!(menu.item_count(), 3);
+  assert_eq!(
+    menu.items(),
+    vec![
+      MenuEntry::Item {
+        position: 0,
+        id: MenuId(701),
+        title: "Open".into(),
+      },
+      MenuEntry::Separator { position: 1 },
+      MenuEntry::Submenu {
+        position: 2,
+        title: "Recent".into(),
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_menu_builder() {
+  use crate::menu::{ContextMenu, MenuBuilder};
+
+  let accel = Accelerator::new(ModifiersState::CONTROL, crate::keyboard::KeyCode::KeyO);
+  let (menu, items) = MenuBuilder::<ContextMenu>::new()
+    .item(MenuId(711), "Open")
+    .accelerator(accel)
+    .item(MenuId(712), "Save")
+    .enabled(false)
+    .native_item(MenuItem::Separator)
+    .submenu("Recent", true, |recent| {
+      recent.item(MenuId(713), "Wrap").selected(true)
+    })
+    .build();
+  let menu = menu.0.menu_platform;
+
+  assert_eq!(
+    menu.items(),
+    vec![
+      MenuEntry::Item {
+        position: 0,
+        id: MenuId(711),
+        title: "Open".into(),
+      },
+      MenuEntry::Item {
+        position: 1,
+        id: MenuId(712),
+        title: "Save".into(),
+      },
+      MenuEntry::Separator { position: 2 },
+      MenuEntry::Submenu {
+        position: 3,
+        title: "Recent".into(),
+      },
+    ]
+  );
+  assert_eq!(items.len(), 3);
+  assert!(items[&MenuId(711)].0.is_enabled());
+  assert!(!items[&MenuId(712)].0.is_enabled());
+  assert!(items[&MenuId(713)].0.is_selected());
+  assert_eq!(menu.accels().map(|accels| accels.len()), Some(1));
+}
+
+#[test]
+fn test_section_headers() {
+  let mut menu = Menu::new_popup_menu();
+  menu.add_section_header("R&D");
+  menu.add_item(
+    MenuId(702),
+    "Report",
+    None,
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+
+  assert_eq!(
+    menu.items(),
+    vec![
+      MenuEntry::SectionHeader {
+        position: 0,
+        label: "R&D".into(),
+      },
+      MenuEntry::Item {
+        position: 1,
+        id: MenuId(702),
+        title: "Report".into(),
+      },
+    ]
+  );
+  unsafe {
+    assert!(is_section_header(menu.hmenu(), 0));
+    assert!(!is_section_header(menu.hmenu(), 702));
+  }
+}
+
+#[test]
+fn test_set_title() {
+  let mut menu = Menu::new_popup_menu();
+  let accel = Accelerator::new(ModifiersState::CONTROL, crate::keyboard::KeyCode::KeyN);
+  let mut item = menu.add_item(
+    MenuId(702),
+    "New",
+    Some(accel.clone()),
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+  item.0.set_title("🦀 新しいファイル");
+  let title = unsafe { menu_item_title(menu.hmenu(), 702) }.unwrap();
+  assert_eq!(title, format!("🦀 新しいファイル\t{}", accel));
+}
+
+#[test]
+fn test_item_state() {
+  let mut menu = Menu::new_popup_menu();
+  let item = menu.add_item(
+    MenuId(703),
+    "Bold",
+    None,
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+  let mut item = item.0;
+  assert!(item.is_enabled());
+  assert!(!item.is_selected());
+
+  item.set_enabled(false);
+  item.set_selected(true);
+  assert!(!item.is_enabled());
+  assert!(item.is_selected());
+
+  // the state of hidden items is remembered
+  item.set_visible(false);
+  assert!(!item.is_enabled());
+  assert!(item.is_selected());
+}
+
+#[test]
+fn test_accel_debug() {
+  let accelerator = Accelerator::new(
+    ModifiersState::CONTROL | ModifiersState::SHIFT,
+    crate::keyboard::KeyCode::KeyS,
+  );
+  let accel = AccelWrapper(convert_accelerator(704, accelerator).unwrap());
+  assert_eq!(
+    format!("{:?}", accel),
+    r#"AccelWrapper { key: 0x53, modifiers: ["Ctrl", "Shift"], cmd: 704 }"#
+  );
+}
+
+#[test]
+fn test_accels_by_menu() {
+  let accel = Accelerator::new(ModifiersState::CONTROL, crate::keyboard::KeyCode::KeyP);
+  let mut first = Menu::new_popup_menu();
+  let item = first.add_item(
+    MenuId(705),
+    "Print",
+    Some(accel.clone()),
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+  let mut second = Menu::new_popup_menu();
+  second.add_item(
+    MenuId(705),
+    "Print",
+    Some(accel),
+    true,
+    false,
+    MenuType::ContextMenu,
+  );
+
+  // the other menu keeps its accelerator for the same id
+  first.remove_item(&item);
+  assert!(first.accels().is_none());
+  assert_eq!(second.accels().map(|accels| accels.len()), Some(1));
+}
@@ -1,8 +1,11 @@
-use std::mem::MaybeUninit;
+use std::{mem::MaybeUninit, ptr};
 
 use windows::Win32::{
   Foundation::{HWND, LPARAM, LRESULT, WPARAM},
-  UI::WindowsAndMessaging::{self as win32wm, *},
+  UI::{
+    Input::Ime::*,
+    WindowsAndMessaging::{self as win32wm, *},
+  },
 };
 
 use crate::platform_impl::platform::event_loop::ProcResult;
@@ -81,7 +84,22 @@ impl MinimalIme {
             return result;
           }
         } else {
-          return String::from_utf16(&[wparam.0 as u16]).ok();
+          // characters outside of the BMP come as a high surrogate followed by a low one
+          let unit = wparam.0 as u16;
+          if (0xD800..=0xDBFF).contains(&unit) {
+            self.utf16parts.clear();
+            self.utf16parts.push(unit);
+            return None;
+          }
+          // a high surrogate that isn't followed by a low one can't be decoded, so drop it
+          // rather than the character that came after it
+          if !(0xDC00..=0xDFFF).contains(&unit) {
+            self.utf16parts.clear();
+          }
+          self.utf16parts.push(unit);
+          let result = String::from_utf16(&self.utf16parts).ok();
+          self.utf16parts.clear();
+          return result;
         }
       }
       _ => (),
@@ -90,3 +108,71 @@ impl MinimalIme {
     None
   }
 }
+
+/// Returns the text accepted at the end of a composition, for a `WM_IME_COMPOSITION` with
+/// `GCS_RESULTSTR`.
+pub(crate) unsafe fn composition_result(hwnd: HWND) -> Option<String> {
+  with_input_context(hwnd, |himc| {
+    let text = utf16_from_bytes(&composition_data(himc, GCS_RESULTSTR)?);
+    Some(String::from_utf16_lossy(&text))
+  })
+}
+
+/// Returns the text being composed and the byte range of its selected clause, or of the cursor,
+/// for a `WM_IME_COMPOSITION` with `GCS_COMPSTR`.
+pub(crate) unsafe fn composition_preedit(hwnd: HWND) -> Option<(String, Option<(usize, usize)>)> {
+  with_input_context(hwnd, |himc| {
+    let text = utf16_from_bytes(&composition_data(himc, GCS_COMPSTR)?);
+
+    // the attributes have one byte per UTF-16 unit, the clause being converted is the target
+    let attributes = composition_data(himc, GCS_COMPATTR).unwrap_or_default();
+    let is_target = |attribute: &u8| {
+      *attribute == ATTR_TARGET_CONVERTED as u8 || *attribute == ATTR_TARGET_NOTCONVERTED as u8
+    };
+    let range = match (
+      attributes.iter().position(is_target),
+      attributes.iter().rposition(is_target),
+    ) {
+      (Some(first), Some(last)) => Some((first, last + 1)),
+      _ => {
+        let cursor = ImmGetCompositionStringW(himc, GCS_CURSORPOS, ptr::null_mut(), 0);
+        usize::try_from(cursor).ok().map(|cursor| (cursor, cursor))
+      }
+    };
+
+    let byte_offset = |index: usize| String::from_utf16_lossy(&text[..index.min(text.len())]).len();
+    let range = range.map(|(start, end)| (byte_offset(start), byte_offset(end)));
+    Some((String::from_utf16_lossy(&text), range))
+  })
+}
+
+unsafe fn with_input_context<T>(hwnd: HWND, f: impl FnOnce(HIMC) -> Option<T>) -> Option<T> {
+  let himc = ImmGetContext(hwnd);
+  if himc.0 == 0 {
+    return None;
+  }
+  let result = f(himc);
+  ImmReleaseContext(hwnd, himc);
+  result
+}
+
+unsafe fn composition_data(himc: HIMC, kind: u32) -> Option<Vec<u8>> {
+  let size = ImmGetCompositionStringW(himc, kind, ptr::null_mut(), 0);
+  if size < 0 {
+    return None;
+  }
+  let mut data = vec![0u8; size as usize];
+  let size = ImmGetCompositionStringW(himc, kind, data.as_mut_ptr() as _, data.len() as u32);
+  if size < 0 {
+    return None;
+  }
+  data.truncate(size as usize);
+  Some(data)
+}
+
+fn utf16_from_bytes(data: &[u8]) -> Vec<u16> {
+  data
+    .chunks_exact(2)
+    .map(|unit| u16::from_ne_bytes([unit[0], unit[1]]))
+    .collect()
+}
@@ -13,10 +13,14 @@ pub use self::{
   event_loop::{EventLoop, EventLoopProxy, EventLoopWindowTarget},
   global_shortcut::{GlobalShortcut, ShortcutManager},
   icon::WinIcon,
+  keyboard::vk_to_key,
   keycode::{keycode_from_scancode, keycode_to_scancode},
-  menu::{Menu, MenuItemAttributes},
+  menu::{
+    native_item_id, AcceleratorText, Menu, MenuEntry, MenuItemAttributes, MenuItemDrawContext,
+    Submenu,
+  },
   monitor::{MonitorHandle, VideoMode},
-  window::{hit_test, Window},
+  window::{hit_test, BackdropMaterial, ProgressState, ResizeDirection, Window},
 };
 
 pub use self::icon::WinIcon as PlatformIcon;
@@ -121,6 +125,8 @@ fn wrap_device_id(id: isize) -> RootDeviceId {
 pub struct KeyEventExtra {
   pub text_with_all_modifiers: Option<&'static str>,
   pub key_without_modifiers: Key<'static>,
+  pub repeat_count: u16,
+  pub scancode: u32,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -151,9 +151,11 @@ impl MonitorHandle {
     MonitorHandle(hmonitor.0)
   }
 
+  // Monitors can be unplugged at any time, so the getters fall back to empty values for a stale
+  // handle instead of panicking.
   #[inline]
   pub fn name(&self) -> Option<String> {
-    let mut monitor_info = get_monitor_info(self.hmonitor()).unwrap();
+    let mut monitor_info = get_monitor_info(self.hmonitor()).ok()?;
     Some(util::wchar_ptr_to_string(PWSTR(
       monitor_info.szDevice.as_mut_ptr(),
     )))
@@ -161,7 +163,7 @@ impl MonitorHandle {
 
   #[inline]
   pub fn native_identifier(&self) -> String {
-    self.name().unwrap()
+    self.name().unwrap_or_default()
   }
 
   #[inline]
@@ -171,21 +173,42 @@ impl MonitorHandle {
 
   #[inline]
   pub fn size(&self) -> PhysicalSize<u32> {
-    let monitor_info = get_monitor_info(self.hmonitor()).unwrap();
-    PhysicalSize {
-      width: (monitor_info.monitorInfo.rcMonitor.right - monitor_info.monitorInfo.rcMonitor.left)
-        as u32,
-      height: (monitor_info.monitorInfo.rcMonitor.bottom - monitor_info.monitorInfo.rcMonitor.top)
-        as u32,
+    match get_monitor_info(self.hmonitor()) {
+      Ok(monitor_info) => PhysicalSize {
+        width: (monitor_info.monitorInfo.rcMonitor.right - monitor_info.monitorInfo.rcMonitor.left)
+          as u32,
+        height: (monitor_info.monitorInfo.rcMonitor.bottom - monitor_info.monitorInfo.rcMonitor.top)
+          as u32,
+      },
+      Err(_) => PhysicalSize::new(0, 0),
     }
   }
 
   #[inline]
   pub fn position(&self) -> PhysicalPosition<i32> {
-    let monitor_info = get_monitor_info(self.hmonitor()).unwrap();
-    PhysicalPosition {
-      x: monitor_info.monitorInfo.rcMonitor.left,
-      y: monitor_info.monitorInfo.rcMonitor.top,
+    match get_monitor_info(self.hmonitor()) {
+      Ok(monitor_info) => PhysicalPosition {
+        x: monitor_info.monitorInfo.rcMonitor.left,
+        y: monitor_info.monitorInfo.rcMonitor.top,
+      },
+      Err(_) => PhysicalPosition::new(0, 0),
+    }
+  }
+
+  #[inline]
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    match get_monitor_info(self.hmonitor()) {
+      Ok(monitor_info) => {
+        let rect = monitor_info.monitorInfo.rcWork;
+        (
+          PhysicalPosition::new(rect.left, rect.top),
+          PhysicalSize::new(
+            (rect.right - rect.left) as u32,
+            (rect.bottom - rect.top) as u32,
+          ),
+        )
+      }
+      Err(_) => (PhysicalPosition::new(0, 0), PhysicalSize::new(0, 0)),
     }
   }
 
@@ -194,6 +217,24 @@ impl MonitorHandle {
     dpi_to_scale_factor(get_monitor_dpi(self.hmonitor()).unwrap_or(96))
   }
 
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    let mut monitor_info = get_monitor_info(self.hmonitor()).ok()?;
+    let device_name = PWSTR(monitor_info.szDevice.as_mut_ptr());
+    unsafe {
+      let mut mode: DEVMODEW = mem::zeroed();
+      mode.dmSize = mem::size_of_val(&mode) as u16;
+      if !EnumDisplaySettingsExW(device_name, ENUM_CURRENT_SETTINGS, &mut mode, 0).as_bool() {
+        return None;
+      }
+      // 0 and 1 stand for the default rate of the hardware
+      match mode.dmDisplayFrequency {
+        0 | 1 => None,
+        frequency => Some(frequency * 1000),
+      }
+    }
+  }
+
   #[inline]
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     // EnumDisplaySettingsExW can return duplicate values (or some of the
@@ -204,7 +245,10 @@ impl MonitorHandle {
 
     loop {
       unsafe {
-        let mut monitor_info = get_monitor_info(self.hmonitor()).unwrap();
+        let mut monitor_info = match get_monitor_info(self.hmonitor()) {
+          Ok(monitor_info) => monitor_info,
+          Err(_) => break,
+        };
         let device_name = PWSTR(monitor_info.szDevice.as_mut_ptr());
         let mut mode: DEVMODEW = mem::zeroed();
         mode.dmSize = mem::size_of_val(&mode) as u16;
@@ -215,9 +259,12 @@ impl MonitorHandle {
         }
         i += 1;
 
+        // some drivers list modes without all the fields, skip them instead of guessing
         const REQUIRED_FIELDS: u32 =
           (DM_BITSPERPEL | DM_PELSWIDTH | DM_PELSHEIGHT | DM_DISPLAYFREQUENCY) as u32;
-        assert!(mode.dmFields & REQUIRED_FIELDS == REQUIRED_FIELDS);
+        if mode.dmFields & REQUIRED_FIELDS != REQUIRED_FIELDS {
+          continue;
+        }
 
         modes.insert(RootVideoMode {
           video_mode: VideoMode {
@@ -3,7 +3,7 @@
 
 use super::{
   dpi::{dpi_to_scale_factor, hwnd_dpi},
-  menu::{subclass_proc as menu_subclass_proc, Menu, MenuHandler},
+  menu::{destroy_menu, subclass_proc as menu_subclass_proc, Menu, MenuHandler},
   util, OsError,
 };
 use crate::{
@@ -11,6 +11,7 @@ use crate::{
   error::OsError as RootOsError,
   event::{Event, Rectangle, TrayEvent},
   event_loop::EventLoopWindowTarget,
+  icon::Icon,
   menu::MenuType,
   system_tray::SystemTray as RootSystemTray,
 };
@@ -28,9 +29,21 @@ const WM_USER_UPDATE_TRAYMENU: u32 = 6002;
 const TRAYICON_UID: u32 = 6003;
 const TRAY_SUBCLASS_ID: usize = 6004;
 const TRAY_MENU_SUBCLASS_ID: usize = 6005;
+// sent through `WM_USER_TRAYICON` when the user clicks a notification
+const NIN_BALLOONUSERCLICK: u32 = WM_USER + 5;
+
+lazy_static! {
+  // Broadcast to top-level windows when the taskbar is created, e.g. after Explorer restarts.
+  static ref TASKBAR_CREATED_MSG_ID: u32 = unsafe { RegisterWindowMessageA("TaskbarCreated") };
+}
 
 struct TrayLoopData {
   hmenu: Option<HMENU>,
+  // kept to add the icon again to a new taskbar
+  hicon: Option<HICON>,
+  tooltip: Option<String>,
+  // kept alive while the notification is shown
+  notification_icon: Option<Icon>,
   sender: Box<dyn Fn(Event<'static, ()>)>,
 }
 
@@ -50,7 +63,10 @@ impl SystemTrayBuilder {
     self,
     window_target: &EventLoopWindowTarget<T>,
   ) -> Result<RootSystemTray, RootOsError> {
-    let hmenu: Option<HMENU> = self.tray_menu.map(|m| m.hmenu());
+    let hmenu: Option<HMENU> = self.tray_menu.as_ref().map(|m| {
+      m.release();
+      m.hmenu()
+    });
 
     let mut class_name = util::to_wstring("tao_system_tray_app");
     unsafe {
@@ -100,13 +116,13 @@ impl SystemTrayBuilder {
         )));
       }
 
-      let system_tray = SystemTray { hwnd };
-      system_tray.set_icon_from_buffer(&self.icon, 32, 32);
-
       // system_tray event handler
       let event_loop_runner = window_target.p.runner_shared.clone();
       let traydata = TrayLoopData {
         hmenu,
+        hicon: None,
+        tooltip: None,
+        notification_icon: None,
         sender: Box::new(move |event| {
           if let Ok(e) = event.map_nonuser_event() {
             event_loop_runner.send_event(e)
@@ -120,17 +136,14 @@ impl SystemTrayBuilder {
         Box::into_raw(Box::new(traydata)) as _,
       );
 
+      let system_tray = SystemTray { hwnd };
+      system_tray.set_icon_from_buffer(&self.icon, 32, 32);
+
       // system_tray menu event handler
-      let event_loop_runner = window_target.p.runner_shared.clone();
-      let menu_handler = MenuHandler::new(
-        Box::new(move |event| {
-          if let Ok(e) = event.map_nonuser_event() {
-            event_loop_runner.send_event(e)
-          }
-        }),
-        MenuType::ContextMenu,
-        None,
-      );
+      let mut menu_handler = MenuHandler::new(&window_target.p, MenuType::ContextMenu, None);
+      if let Some(tray_menu) = &self.tray_menu {
+        menu_handler.set_menu(tray_menu);
+      }
       SetWindowSubclass(
         hwnd,
         Some(menu_subclass_proc),
@@ -170,11 +183,104 @@ impl SystemTray {
       if !Shell_NotifyIconW(NIM_MODIFY, &mut nid as _).as_bool() {
         debug!("Error setting icon");
       }
+
+      if let Some(traydata) = self.tray_data() {
+        if let Some(previous) = traydata.hicon.replace(icon) {
+          DestroyIcon(previous);
+        }
+      }
+    }
+  }
+
+  // Available only with SystemTrayExtWindows
+  pub fn set_tray_icon(&mut self, icon: Icon) {
+    unsafe {
+      // the tray owns its icon, while `icon` may still be used elsewhere
+      let hicon = CopyIcon(icon.inner.as_raw_handle());
+      if !hicon.is_invalid() {
+        self.set_hicon(hicon);
+      }
+    }
+  }
+
+  pub fn set_tooltip(&mut self, tooltip: &str) {
+    unsafe {
+      let mut nid = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as _,
+        uFlags: NIF_TIP,
+        hWnd: self.hwnd,
+        uID: TRAYICON_UID,
+        ..std::mem::zeroed()
+      };
+      copy_truncated(&mut nid.szTip, tooltip);
+      if !Shell_NotifyIconW(NIM_MODIFY, &mut nid as _).as_bool() {
+        debug!("Error setting the tooltip of the system tray icon");
+      }
+
+      if let Some(traydata) = self.tray_data() {
+        traydata.tooltip = Some(tooltip.to_string());
+      }
+    }
+  }
+
+  unsafe fn tray_data(&self) -> Option<&mut TrayLoopData> {
+    let mut traydata: usize = 0;
+    GetWindowSubclass(
+      self.hwnd,
+      Some(tray_subclass_proc),
+      TRAY_SUBCLASS_ID,
+      &mut traydata,
+    )
+    .as_bool()
+    .then(|| &mut *(traydata as *mut TrayLoopData))
+  }
+
+  // Available only with SystemTrayExtWindows
+  pub fn show_notification(&self, title: &str, body: &str, icon: Option<Icon>) {
+    unsafe {
+      let mut nid = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as _,
+        uFlags: NIF_INFO,
+        hWnd: self.hwnd,
+        uID: TRAYICON_UID,
+        // without a notification icon, the icon of the tray is used
+        dwInfoFlags: NIIF_USER | NIIF_LARGE_ICON | NIIF_RESPECT_QUIET_TIME,
+        hBalloonIcon: icon
+          .as_ref()
+          .map(|icon| icon.inner.as_raw_handle())
+          .unwrap_or_default(),
+        ..std::mem::zeroed()
+      };
+      copy_truncated(&mut nid.szInfoTitle, title);
+      copy_truncated(&mut nid.szInfo, body);
+      // fails e.g. when notifications are turned off, which isn't worth reporting
+      if !Shell_NotifyIconW(NIM_MODIFY, &mut nid as _).as_bool() {
+        debug!("Error showing the notification of the system tray icon");
+        return;
+      }
+
+      if let Some(traydata) = self.tray_data() {
+        traydata.notification_icon = icon;
+      }
     }
   }
 
   pub fn set_menu(&mut self, tray_menu: &Menu) {
     unsafe {
+      // the menu handler only sends events for the items of the current menu
+      let mut menu_handler: usize = 0;
+      if GetWindowSubclass(
+        self.hwnd,
+        Some(menu_subclass_proc),
+        TRAY_MENU_SUBCLASS_ID,
+        &mut menu_handler,
+      )
+      .as_bool()
+      {
+        (*(menu_handler as *mut MenuHandler)).set_menu(tray_menu);
+      }
+
+      tray_menu.release();
       // send the new menu to the subclass proc where we will update there
       SendMessageW(
         self.hwnd,
@@ -218,17 +324,48 @@ unsafe extern "system" fn tray_subclass_proc(
   let mut subclass_input = &mut *(subclass_input_ptr);
 
   if msg == WM_DESTROY {
+    if let Some(hmenu) = subclass_input.hmenu {
+      destroy_menu(hmenu);
+    }
+    if let Some(hicon) = subclass_input.hicon {
+      DestroyIcon(hicon);
+    }
     Box::from_raw(subclass_input_ptr);
+    return DefSubclassProc(hwnd, msg, wparam, lparam);
+  }
+
+  // the icons of the previous taskbar are lost
+  if msg == *TASKBAR_CREATED_MSG_ID {
+    let mut nid = NOTIFYICONDATAW {
+      cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as _,
+      uFlags: NIF_MESSAGE | NIF_ICON | NIF_TIP,
+      hWnd: hwnd,
+      uID: TRAYICON_UID,
+      uCallbackMessage: WM_USER_TRAYICON,
+      hIcon: subclass_input.hicon.unwrap_or_default(),
+      ..std::mem::zeroed()
+    };
+    if let Some(tooltip) = &subclass_input.tooltip {
+      copy_truncated(&mut nid.szTip, tooltip);
+    }
+    if !Shell_NotifyIconW(NIM_ADD, &mut nid as _).as_bool() {
+      debug!("Error adding the system tray icon to the new taskbar");
+    }
   }
 
   if msg == WM_USER_UPDATE_TRAYMENU {
-    subclass_input.hmenu = Some(HMENU(wparam.0 as _));
+    let hmenu = HMENU(wparam.0 as _);
+    if let Some(previous) = subclass_input.hmenu.replace(hmenu) {
+      if previous != hmenu {
+        destroy_menu(previous);
+      }
+    }
   }
 
   if msg == WM_USER_TRAYICON
     && matches!(
       lparam.0 as u32,
-      WM_LBUTTONUP | WM_RBUTTONUP | WM_LBUTTONDBLCLK
+      WM_LBUTTONUP | WM_RBUTTONUP | WM_LBUTTONDBLCLK | NIN_BALLOONUSERCLICK
     )
   {
     let nid = NOTIFYICONIDENTIFIER {
@@ -284,6 +421,14 @@ unsafe extern "system" fn tray_subclass_proc(
         });
       }
 
+      NIN_BALLOONUSERCLICK => {
+        (subclass_input.sender)(Event::TrayEvent {
+          event: TrayEvent::NotificationClick,
+          position,
+          bounds,
+        });
+      }
+
       _ => {}
     }
   }
@@ -307,3 +452,14 @@ unsafe fn show_tray_menu(hwnd: HWND, menu: HMENU, x: i32, y: i32) {
     std::ptr::null_mut(),
   );
 }
+
+// Copies `text` into the fixed size buffer `dst`, truncated to leave room for the nul terminator.
+fn copy_truncated(dst: &mut [u16], text: &str) {
+  let mut text: Vec<u16> = text.encode_utf16().take(dst.len() - 1).collect();
+  // don't leave half of a surrogate pair
+  if text.len() == dst.len() - 1 && matches!(text.last(), Some(0xD800..=0xDBFF)) {
+    text.pop();
+  }
+  dst[..text.len()].copy_from_slice(&text);
+  dst[text.len()] = 0;
+}
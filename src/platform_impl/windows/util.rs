@@ -16,7 +16,10 @@ use windows::{
   Win32::{
     Foundation::{BOOL, FARPROC, HWND, LPARAM, LRESULT, POINT, PWSTR, RECT, WPARAM},
     Globalization::lstrlenW,
-    Graphics::Gdi::{ClientToScreen, InvalidateRgn, HMONITOR, HRGN},
+    Graphics::Gdi::{
+      ClientToScreen, CreateCompatibleDC, CreateDIBSection, DeleteDC, DeleteObject, InvalidateRgn,
+      SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP, HMONITOR, HRGN,
+    },
     System::LibraryLoader::*,
     UI::{
       HiDpi::*,
@@ -130,6 +133,10 @@ pub(crate) fn set_inner_size_physical(window: HWND, x: u32, y: u32) {
 }
 
 pub fn adjust_window_rect(hwnd: HWND, rect: RECT) -> Option<RECT> {
+  // `WM_NCCALCSIZE` removes the frame of undecorated windows, whatever their styles
+  if has_no_frame(hwnd) {
+    return Some(rect);
+  }
   unsafe {
     let style = GetWindowLongW(hwnd, GWL_STYLE) as WINDOW_STYLE;
     let style_ex = GetWindowLongW(hwnd, GWL_EXSTYLE) as WINDOW_EX_STYLE;
@@ -137,6 +144,19 @@ pub fn adjust_window_rect(hwnd: HWND, rect: RECT) -> Option<RECT> {
   }
 }
 
+fn has_no_frame(hwnd: HWND) -> bool {
+  let mut client_rect = RECT::default();
+  match (get_window_rect(hwnd), unsafe {
+    GetClientRect(hwnd, &mut client_rect).as_bool()
+  }) {
+    (Some(window_rect), true) => {
+      window_rect.right - window_rect.left == client_rect.right
+        && window_rect.bottom - window_rect.top == client_rect.bottom
+    }
+    _ => false,
+  }
+}
+
 pub fn adjust_window_rect_with_styles(
   hwnd: HWND,
   style: WINDOW_STYLE,
@@ -273,6 +293,51 @@ pub fn get_hicon_from_buffer(buffer: &[u8], width: i32, height: i32) -> Option<H
   }
 }
 
+/// Draw `hicon` into a new 32-bit bitmap, keeping its transparency.
+pub fn get_hbitmap_from_hicon(hicon: HICON, width: i32, height: i32) -> Option<HBITMAP> {
+  unsafe {
+    let hdc = CreateCompatibleDC(None);
+    if hdc.is_invalid() {
+      debug!("Unable to CreateCompatibleDC");
+      return None;
+    }
+
+    let info = BITMAPINFO {
+      bmiHeader: BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as _,
+        biWidth: width,
+        // top-down
+        biHeight: -height,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB as _,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let mut bits = ptr::null_mut();
+    let hbitmap = CreateDIBSection(hdc, &info, DIB_RGB_COLORS, &mut bits, None, 0);
+    if hbitmap.is_invalid() {
+      debug!("Unable to CreateDIBSection");
+      DeleteDC(hdc);
+      return None;
+    }
+
+    let previous = SelectObject(hdc, hbitmap);
+    let drawn = DrawIconEx(hdc, 0, 0, hicon, width, height, 0, None, DI_NORMAL).as_bool();
+    SelectObject(hdc, previous);
+    DeleteDC(hdc);
+
+    if drawn {
+      Some(hbitmap)
+    } else {
+      debug!("Unable to DrawIconEx");
+      DeleteObject(hbitmap);
+      None
+    }
+  }
+}
+
 impl CursorIcon {
   pub(crate) fn to_windows_cursor(self) -> PWSTR {
     match self {
@@ -338,6 +403,8 @@ pub type GetDpiForMonitor = unsafe extern "system" fn(
   dpi_y: *mut u32,
 ) -> HRESULT;
 pub type EnableNonClientDpiScaling = unsafe extern "system" fn(hwnd: HWND) -> BOOL;
+pub type GetSystemMetricsForDpi =
+  unsafe extern "system" fn(index: SYSTEM_METRICS_INDEX, dpi: u32) -> i32;
 pub type AdjustWindowRectExForDpi = unsafe extern "system" fn(
   rect: *mut RECT,
   dwStyle: WINDOW_STYLE,
@@ -355,6 +422,8 @@ lazy_static! {
     get_function!("shcore.dll", GetDpiForMonitor);
   pub static ref ENABLE_NON_CLIENT_DPI_SCALING: Option<EnableNonClientDpiScaling> =
     get_function!("user32.dll", EnableNonClientDpiScaling);
+  pub static ref GET_SYSTEM_METRICS_FOR_DPI: Option<GetSystemMetricsForDpi> =
+    get_function!("user32.dll", GetSystemMetricsForDpi);
   pub static ref SET_PROCESS_DPI_AWARENESS_CONTEXT: Option<SetProcessDpiAwarenessContext> =
     get_function!("user32.dll", SetProcessDpiAwarenessContext);
   pub static ref SET_PROCESS_DPI_AWARENESS: Option<SetProcessDpiAwareness> =
@@ -19,11 +19,20 @@ use crossbeam_channel as channel;
 use windows::Win32::{
   Foundation::{self as win32f, HINSTANCE, HWND, LPARAM, LRESULT, POINT, PWSTR, RECT, WPARAM},
   Graphics::{
-    Dwm::{DwmEnableBlurBehindWindow, DWM_BB_BLURREGION, DWM_BB_ENABLE, DWM_BLURBEHIND},
+    Dwm::{
+      DwmEnableBlurBehindWindow, DwmExtendFrameIntoClientArea, DwmSetWindowAttribute,
+      DWMWINDOWATTRIBUTE, DWM_BB_BLURREGION, DWM_BB_ENABLE, DWM_BLURBEHIND,
+    },
     Gdi::*,
   },
-  System::{Com::*, LibraryLoader::*, Ole::*},
+  System::{
+    Com::*,
+    LibraryLoader::*,
+    Ole::*,
+    Threading::{AttachThreadInput, GetCurrentThreadId},
+  },
   UI::{
+    Controls::MARGINS,
     Input::{Ime::*, KeyboardAndMouse::*, Touch::*},
     Shell::*,
     WindowsAndMessaging::{self as win32wm, *},
@@ -31,13 +40,15 @@ use windows::Win32::{
 };
 
 use crate::{
+  accelerator::Accelerator,
   dpi::{PhysicalPosition, PhysicalSize, Position, Size},
   error::{ExternalError, NotSupportedError, OsError as RootOsError},
   icon::Icon,
-  menu::MenuType,
+  menu::{MenuId, MenuType},
   monitor::MonitorHandle as RootMonitorHandle,
   platform_impl::platform::{
-    dark_mode::try_theme,
+    accelerator::register_window_accel,
+    dark_mode::{refresh_title_bar, try_theme, WIN10_BUILD_VERSION},
     dpi::{dpi_to_scale_factor, hwnd_dpi},
     drop_handler::FileDropHandler,
     event_loop::{self, EventLoopWindowTarget, DESTROY_MSG_ID},
@@ -47,8 +58,8 @@ use crate::{
     OsError, Parent, PlatformSpecificWindowBuilderAttributes, WindowId,
   },
   window::{
-    CursorIcon, Fullscreen, Theme, UserAttentionType, WindowAttributes, WindowId as RootWindowId,
-    BORDERLESS_RESIZE_INSET,
+    CursorGrabMode, CursorIcon, Fullscreen, Theme, UserAttentionType, WindowAttributes,
+    WindowId as RootWindowId, BORDERLESS_RESIZE_INSET,
   },
 };
 
@@ -56,6 +67,65 @@ struct HMenuWrapper(HMENU);
 unsafe impl Send for HMenuWrapper {}
 unsafe impl Sync for HMenuWrapper {}
 
+/// The edge or corner a window is resized from, see [`WindowExtWindows::drag_resize_window`].
+///
+/// [`WindowExtWindows::drag_resize_window`]: crate::platform::windows::WindowExtWindows::drag_resize_window
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeDirection {
+  /// The right edge.
+  East,
+  /// The top edge.
+  North,
+  /// The top right corner.
+  NorthEast,
+  /// The top left corner.
+  NorthWest,
+  /// The bottom edge.
+  South,
+  /// The bottom right corner.
+  SouthEast,
+  /// The bottom left corner.
+  SouthWest,
+  /// The left edge.
+  West,
+}
+
+/// The state of the progress bar shown over the taskbar button of a window, see
+/// [`WindowExtWindows::set_progress_bar`].
+///
+/// [`WindowExtWindows::set_progress_bar`]: crate::platform::windows::WindowExtWindows::set_progress_bar
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressState {
+  /// No progress bar is shown.
+  None,
+  /// A green progress bar.
+  Normal,
+  /// A yellow progress bar.
+  Paused,
+  /// A red progress bar.
+  Error,
+  /// A green bar cycling along the button, when the progress can't be measured.
+  Indeterminate,
+}
+
+/// The system material drawn behind a window, see [`WindowExtWindows::set_backdrop`].
+///
+/// [`WindowExtWindows::set_backdrop`]: crate::platform::windows::WindowExtWindows::set_backdrop
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackdropMaterial {
+  /// No material, the default.
+  None,
+  /// The tinted material of long-lived windows, following the desktop wallpaper.
+  Mica,
+  /// The blurred, translucent material of transient windows like popups.
+  Acrylic,
+  /// A variant of Mica for windows with tabs in their title bar.
+  Tabbed,
+}
+
 /// The Win32 implementation of the main `Window` object.
 pub struct Window {
   /// Main handle for the window.
@@ -124,6 +194,11 @@ impl Window {
           _file_drop_handler: file_drop_handler,
           subclass_removed: Cell::new(false),
           recurse_depth: Cell::new(0),
+          accelerator_handler: menu::MenuHandler::new(
+            event_loop,
+            MenuType::MenuBar,
+            Some(RootWindowId(win.id())),
+          ),
         };
 
         event_loop::subclass_window(win.window.0, subclass_input);
@@ -149,6 +224,9 @@ impl Window {
       WindowState::set_window_flags(window_state.lock(), window.0, |f| {
         f.set(WindowFlags::VISIBLE, visible)
       });
+      if visible && window_state.lock().skip_taskbar {
+        unsafe { taskbar_set_skip(window.0, true) };
+      }
     });
   }
 
@@ -257,17 +335,37 @@ impl Window {
   #[inline]
   pub fn set_min_inner_size(&self, size: Option<Size>) {
     self.window_state.lock().min_size = size;
-    // Make windows re-check the window size bounds.
-    let size = self.inner_size();
-    self.set_inner_size(size.into());
+    self.clamp_inner_size();
   }
 
   #[inline]
   pub fn set_max_inner_size(&self, size: Option<Size>) {
     self.window_state.lock().max_size = size;
-    // Make windows re-check the window size bounds.
+    self.clamp_inner_size();
+  }
+
+  // Resizes the window into its size bounds right away, which `WM_GETMINMAXINFO` only enforces
+  // when the window is resized. A maximized window is left as is, instead of being restored.
+  fn clamp_inner_size(&self) {
+    let (min_size, max_size, scale_factor) = {
+      let window_state = self.window_state.lock();
+      (
+        window_state.min_size,
+        window_state.max_size,
+        window_state.scale_factor,
+      )
+    };
     let size = self.inner_size();
-    self.set_inner_size(size.into());
+    let clamped: PhysicalSize<u32> = Size::clamp(
+      size.into(),
+      min_size.unwrap_or_else(|| PhysicalSize::new(0, 0).into()),
+      max_size.unwrap_or_else(|| PhysicalSize::new(u32::MAX, u32::MAX).into()),
+      scale_factor,
+    )
+    .to_physical(scale_factor);
+    if clamped != size && !util::is_maximized(self.window.0) {
+      util::set_inner_size_physical(self.window.0, clamped.width, clamped.height);
+    }
   }
 
   #[inline]
@@ -282,6 +380,30 @@ impl Window {
     });
   }
 
+  #[inline]
+  pub fn set_minimizable(&self, minimizable: bool) {
+    let window = self.window.clone();
+    let window_state = Arc::clone(&self.window_state);
+
+    self.thread_executor.execute_in_thread(move || {
+      WindowState::set_window_flags(window_state.lock(), window.0, |f| {
+        f.set(WindowFlags::MINIMIZABLE, minimizable)
+      });
+    });
+  }
+
+  #[inline]
+  pub fn set_maximizable(&self, maximizable: bool) {
+    let window = self.window.clone();
+    let window_state = Arc::clone(&self.window_state);
+
+    self.thread_executor.execute_in_thread(move || {
+      WindowState::set_window_flags(window_state.lock(), window.0, |f| {
+        f.set(WindowFlags::MAXIMIZABLE, maximizable)
+      });
+    });
+  }
+
   /// Returns the `hwnd` of this window.
   #[inline]
   pub fn hwnd(&self) -> HWND {
@@ -304,14 +426,25 @@ impl Window {
   #[inline]
   pub fn set_cursor_icon(&self, cursor: CursorIcon) {
     self.window_state.lock().mouse.cursor = cursor;
+    let window = self.window.clone();
     self.thread_executor.execute_in_thread(move || unsafe {
-      let cursor = LoadCursorW(HINSTANCE::default(), cursor.to_windows_cursor());
-      SetCursor(cursor);
+      // `WM_SETCURSOR` applies the cursor from now on, only update it right away when it is over
+      // the client area, not to replace the cursor of the borders or of another window
+      let mut point = POINT::default();
+      if !GetCursorPos(&mut point).as_bool() || WindowFromPoint(point) != window.0 {
+        return;
+      }
+      let lparam = util::MAKELPARAM(point.x as i16, point.y as i16);
+      let hit_test = SendMessageW(window.0, WM_NCHITTEST, WPARAM(0), lparam);
+      if hit_test.0 as u32 == HTCLIENT {
+        let cursor = LoadCursorW(HINSTANCE::default(), cursor.to_windows_cursor());
+        SetCursor(cursor);
+      }
     });
   }
 
   #[inline]
-  pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
+  pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
     let window = self.window.clone();
     let window_state = Arc::clone(&self.window_state);
     let (tx, rx) = channel::unbounded();
@@ -320,7 +453,10 @@ impl Window {
       let result = window_state
         .lock()
         .mouse
-        .set_cursor_flags(window.0, |f| f.set(CursorFlags::GRABBED, grab))
+        .set_cursor_flags(window.0, |f| {
+          f.set(CursorFlags::GRABBED, mode == CursorGrabMode::Confined);
+          f.set(CursorFlags::LOCKED, mode == CursorGrabMode::Locked);
+        })
         .map_err(|e| ExternalError::Os(os_error!(OsError::IoError(e))));
       let _ = tx.send(result);
     });
@@ -370,16 +506,58 @@ impl Window {
     Ok(())
   }
 
+  #[inline]
+  pub fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
+    let mut point = POINT::default();
+    let mut rect = RECT::default();
+    unsafe {
+      if !GetCursorPos(&mut point).as_bool()
+        || !ScreenToClient(self.window.0, &mut point).as_bool()
+        || !GetClientRect(self.window.0, &mut rect).as_bool()
+      {
+        return None;
+      }
+    }
+    let inside =
+      point.x >= rect.left && point.x < rect.right && point.y >= rect.top && point.y < rect.bottom;
+    inside.then(|| PhysicalPosition::new(point.x as f64, point.y as f64))
+  }
+
   #[inline]
   pub fn drag_window(&self) -> Result<(), ExternalError> {
+    self.begin_non_client_drag(WPARAM(HTCAPTION as _))
+  }
+
+  #[inline]
+  pub(crate) fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+    let hit_test = match direction {
+      ResizeDirection::East => HTRIGHT,
+      ResizeDirection::North => HTTOP,
+      ResizeDirection::NorthEast => HTTOPRIGHT,
+      ResizeDirection::NorthWest => HTTOPLEFT,
+      ResizeDirection::South => HTBOTTOM,
+      ResizeDirection::SouthEast => HTBOTTOMRIGHT,
+      ResizeDirection::SouthWest => HTBOTTOMLEFT,
+      ResizeDirection::West => HTLEFT,
+    };
+    self.begin_non_client_drag(WPARAM(hit_test as _))
+  }
+
+  // Moves or resizes the window as if the left mouse button was pressed over `hit_test`, until
+  // the button is released.
+  fn begin_non_client_drag(&self, hit_test: WPARAM) -> Result<(), ExternalError> {
     let mut pos = POINT::default();
     unsafe {
+      // the drag would otherwise start with the next click on the window
+      if GetKeyState(i32::from(VK_LBUTTON)) >= 0 {
+        return Err(ExternalError::NotSupported(NotSupportedError::new()));
+      }
       GetCursorPos(&mut pos);
       ReleaseCapture();
       PostMessageW(
         self.window.0,
         WM_NCLBUTTONDOWN,
-        WPARAM(HTCAPTION as _),
+        hit_test,
         util::MAKELPARAM(pos.x as i16, pos.y as i16),
       );
     }
@@ -534,14 +712,19 @@ impl Window {
       // Update window bounds
       match &fullscreen {
         Some(fullscreen) => {
-          // Save window bounds before entering fullscreen
-          let placement = unsafe {
-            let mut placement = WINDOWPLACEMENT::default();
-            GetWindowPlacement(window.0, &mut placement);
-            placement
-          };
+          // Save window bounds before entering fullscreen. Moving to another mode or monitor
+          // keeps the bounds saved when leaving the windowed state, instead of the fullscreen ones
+          let mut window_state_lock = window_state.lock();
+          if window_state_lock.saved_window.is_none() {
+            let placement = unsafe {
+              let mut placement = WINDOWPLACEMENT::default();
+              GetWindowPlacement(window.0, &mut placement);
+              placement
+            };
 
-          window_state.lock().saved_window = Some(SavedWindow { placement });
+            window_state_lock.saved_window = Some(SavedWindow { placement });
+          }
+          drop(window_state_lock);
 
           let monitor = match &fullscreen {
             Fullscreen::Exclusive(video_mode) => video_mode.monitor(),
@@ -591,9 +774,50 @@ impl Window {
     let window_state = Arc::clone(&self.window_state);
 
     self.thread_executor.execute_in_thread(move || {
+      let window_flags = window_state.lock().window_flags();
+      // the frame grows or shrinks around the client area instead of resizing it, unless the
+      // window fills the screen anyway
+      let client_rect = match window_flags.intersects(
+        WindowFlags::MAXIMIZED
+          | WindowFlags::MINIMIZED
+          | WindowFlags::MARKER_EXCLUSIVE_FULLSCREEN
+          | WindowFlags::MARKER_BORDERLESS_FULLSCREEN,
+      ) {
+        true => None,
+        false => util::get_client_rect(window.0).ok(),
+      };
+
       WindowState::set_window_flags(window_state.lock(), window.0, |f| {
         f.set(WindowFlags::DECORATIONS, decorations)
       });
+
+      if let Some(mut rect) = client_rect.and_then(|rect| util::adjust_window_rect(window.0, rect))
+      {
+        unsafe {
+          // child windows are positioned relative to the client area of their parent
+          if window_flags.contains(WindowFlags::CHILD) {
+            let parent = GetParent(window.0);
+            let mut top_left = POINT {
+              x: rect.left,
+              y: rect.top,
+            };
+            ScreenToClient(parent, &mut top_left);
+            rect.right += top_left.x - rect.left;
+            rect.bottom += top_left.y - rect.top;
+            rect.left = top_left.x;
+            rect.top = top_left.y;
+          }
+          SetWindowPos(
+            window.0,
+            HWND::default(),
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+          );
+        }
+      }
     });
   }
 
@@ -609,6 +833,27 @@ impl Window {
     });
   }
 
+  #[inline]
+  pub fn set_opacity(&self, opacity: f64) {
+    let window = self.window.clone();
+    let window_state = Arc::clone(&self.window_state);
+    let opacity = opacity.clamp(0.0, 1.0);
+
+    self.thread_executor.execute_in_thread(move || {
+      // A fully opaque window doesn't need to be layered, and dropping the style spares us the
+      // cost of redirecting its rendering.
+      let layered = opacity < 1.0;
+      WindowState::set_window_flags(window_state.lock(), window.0, |f| {
+        f.set(WindowFlags::LAYERED, layered)
+      });
+      if layered {
+        unsafe {
+          SetLayeredWindowAttributes(window.0, 0, (opacity * 255.0).round() as u8, LWA_ALPHA);
+        }
+      }
+    });
+  }
+
   #[inline]
   pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
     Some(RootMonitorHandle {
@@ -618,6 +863,7 @@ impl Window {
 
   #[inline]
   pub fn set_window_icon(&self, window_icon: Option<Icon>) {
+    let mut window_state = self.window_state.lock();
     if let Some(ref window_icon) = window_icon {
       window_icon
         .inner
@@ -625,19 +871,30 @@ impl Window {
     } else {
       icon::unset_for_window(self.window.0, IconType::Small);
     }
-    self.window_state.lock().window_icon = window_icon;
+    // the taskbar and Alt+Tab follow the window icon, unless they have their own
+    if window_state.taskbar_icon.is_none() {
+      match window_icon {
+        Some(ref window_icon) => window_icon
+          .inner
+          .set_for_window(self.window.0, IconType::Big),
+        None => icon::unset_for_window(self.window.0, IconType::Big),
+      }
+    }
+    // the previous icon is destroyed once the window no longer uses it
+    window_state.window_icon = window_icon;
   }
 
   #[inline]
   pub fn set_taskbar_icon(&self, taskbar_icon: Option<Icon>) {
-    if let Some(ref taskbar_icon) = taskbar_icon {
-      taskbar_icon
-        .inner
-        .set_for_window(self.window.0, IconType::Big);
-    } else {
-      icon::unset_for_window(self.window.0, IconType::Big);
+    let mut window_state = self.window_state.lock();
+    match taskbar_icon
+      .as_ref()
+      .or_else(|| window_state.window_icon.as_ref())
+    {
+      Some(icon) => icon.inner.set_for_window(self.window.0, IconType::Big),
+      None => icon::unset_for_window(self.window.0, IconType::Big),
     }
-    self.window_state.lock().taskbar_icon = taskbar_icon;
+    window_state.taskbar_icon = taskbar_icon;
   }
 
   pub(crate) fn set_ime_position_physical(&self, x: i32, y: i32) {
@@ -661,11 +918,22 @@ impl Window {
     self.set_ime_position_physical(x, y);
   }
 
+  #[inline]
+  pub fn set_ime_allowed(&self, allowed: bool) {
+    let window = self.window.clone();
+    self.thread_executor.execute_in_thread(move || unsafe {
+      // without an input context, the keys go straight to the window
+      let flags = if allowed { IACE_DEFAULT } else { 0 };
+      ImmAssociateContextEx(window.0, HIMC::default(), flags);
+    });
+  }
+
   #[inline]
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
     let window = self.window.clone();
     let active_window_handle = unsafe { GetActiveWindow() };
-    if window.0 == active_window_handle {
+    // a request to stop flashing is always forwarded
+    if request_type.is_some() && window.0 == active_window_handle {
       return;
     }
 
@@ -673,7 +941,8 @@ impl Window {
       let (flags, count) = request_type
         .map(|ty| match ty {
           UserAttentionType::Critical => (FLASHW_ALL | FLASHW_TIMERNOFG, u32::MAX),
-          UserAttentionType::Informational => (FLASHW_TRAY | FLASHW_TIMERNOFG, 0),
+          // the taskbar button stays highlighted after the last flash, until the window is focused
+          UserAttentionType::Informational => (FLASHW_TRAY, 3),
         })
         .unwrap_or((FLASHW_STOP, 0));
 
@@ -693,6 +962,21 @@ impl Window {
     self.window_state.lock().current_theme
   }
 
+  #[inline]
+  pub fn set_theme(&self, theme: Option<Theme>) {
+    let window = self.window.clone();
+    let window_state = Arc::clone(&self.window_state);
+
+    self.thread_executor.execute_in_thread(move || {
+      let new_theme = try_theme(window.0, theme);
+      let mut window_state = window_state.lock();
+      window_state.preferred_theme = theme;
+      window_state.current_theme = new_theme;
+      drop(window_state);
+      refresh_title_bar(window.0);
+    });
+  }
+
   #[inline]
   pub fn hide_menu(&self) {
     unsafe {
@@ -714,6 +998,42 @@ impl Window {
     unsafe { !GetMenu(self.hwnd()).is_invalid() }
   }
 
+  #[inline]
+  pub fn show_context_menu(&self, menu: &menu::Menu, position: Option<Position>) {
+    let mut point = POINT::default();
+    unsafe {
+      match position {
+        Some(position) => {
+          let (x, y) = position.to_physical::<i32>(self.scale_factor()).into();
+          point = POINT { x, y };
+          ClientToScreen(self.window.0, &mut point);
+        }
+        None => {
+          GetCursorPos(&mut point);
+        }
+      }
+    }
+    menu.show_context_menu(self.window.0, point.x, point.y);
+  }
+
+  #[inline]
+  pub fn set_accelerators(&self, accelerators: &[(MenuId, Accelerator)]) {
+    let accels: Vec<ACCEL> = accelerators
+      .iter()
+      .filter_map(|(menu_id, accelerator)| {
+        let accel = menu::convert_accelerator(menu_id.0, accelerator.clone());
+        if accel.is_none() {
+          warn!(
+            "Unsupported accelerator {:?} for {:?}",
+            accelerator, menu_id
+          );
+        }
+        accel
+      })
+      .collect();
+    register_window_accel(self.window.0, &accels);
+  }
+
   #[inline]
   pub fn reset_dead_keys(&self) {
     // `ToUnicode` consumes the dead-key by default, so we are constructing a fake (but valid)
@@ -745,19 +1065,82 @@ impl Window {
     }
   }
 
+  #[inline]
+  pub(crate) fn set_enable(&self, enabled: bool) {
+    let window = self.window.clone();
+    self.thread_executor.execute_in_thread(move || unsafe {
+      EnableWindow(window.0, enabled);
+      // closing a modal window before enabling its owner can leave no window active, give the
+      // focus back to the owner instead of letting the user look for it
+      if enabled
+        && util::is_visible(window.0)
+        && GetActiveWindow().is_invalid()
+        && GetForegroundWindow().is_invalid()
+      {
+        SetForegroundWindow(window.0);
+      }
+    });
+  }
+
   #[inline]
   pub(crate) fn set_skip_taskbar(&self, skip: bool) {
-    unsafe {
-      com_initialized();
-      let taskbar_list: ITaskbarList =
-        CoCreateInstance(&TaskbarList, None, CLSCTX_SERVER).expect("failed to create TaskBarList");
-      if skip {
-        taskbar_list
-          .DeleteTab(self.hwnd())
-          .expect("DeleteTab failed");
-      } else {
-        taskbar_list.AddTab(self.hwnd()).expect("AddTab failed");
+    let window = self.window.clone();
+    let window_state = Arc::clone(&self.window_state);
+    // the taskbar list is created on the thread of the window
+    self.thread_executor.execute_in_thread(move || {
+      let mut window_state = window_state.lock();
+      let changed = window_state.skip_taskbar != skip;
+      window_state.skip_taskbar = skip;
+      drop(window_state);
+      // a hidden window has no button, it is only removed once shown
+      if changed && util::is_visible(window.0) {
+        unsafe { taskbar_set_skip(window.0, skip) };
       }
+    });
+  }
+
+  #[inline]
+  pub(crate) fn set_progress_bar(&self, state: ProgressState, progress: Option<f64>) {
+    let window = self.window.clone();
+    // the taskbar list is created on the thread of the window
+    self.thread_executor.execute_in_thread(move || unsafe {
+      taskbar_set_progress(window.0, state, progress);
+    });
+  }
+
+  #[inline]
+  pub(crate) fn set_overlay_icon(&self, icon: Option<Icon>, description: Option<&str>) {
+    let window = self.window.clone();
+    let description = description.unwrap_or_default().to_owned();
+    self.thread_executor.execute_in_thread(move || unsafe {
+      taskbar_set_overlay_icon(window.0, icon.as_ref(), &description);
+    });
+  }
+
+  #[inline]
+  pub(crate) fn set_resize_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+    self.window_state.lock().aspect_ratio = aspect_ratio;
+  }
+
+  #[inline]
+  pub(crate) fn set_backdrop(&self, material: BackdropMaterial) {
+    unsafe { set_window_backdrop(self.hwnd(), material) };
+  }
+
+  #[inline]
+  pub(crate) fn set_close_button_enabled(&self, enabled: bool) {
+    unsafe {
+      // the close button and `Alt+F4` follow the state of the item of the system menu
+      let system_menu = GetSystemMenu(self.hwnd(), false);
+      EnableMenuItem(
+        system_menu,
+        SC_CLOSE,
+        MF_BYCOMMAND
+          | match enabled {
+            true => MF_ENABLED,
+            false => MF_GRAYED,
+          },
+      );
     }
   }
 }
@@ -803,6 +1186,8 @@ unsafe fn init<T: 'static>(
   window_flags.set(WindowFlags::TRANSPARENT, attributes.transparent);
   // WindowFlags::VISIBLE and MAXIMIZED are set down below after the window has been configured.
   window_flags.set(WindowFlags::RESIZABLE, attributes.resizable);
+  window_flags.set(WindowFlags::MINIMIZABLE, attributes.minimizable);
+  window_flags.set(WindowFlags::MAXIMIZABLE, attributes.maximizable);
 
   let parent = match pl_attribs.parent {
     Parent::ChildOf(parent) => {
@@ -860,18 +1245,7 @@ unsafe fn init<T: 'static>(
 
   // making the window transparent
   if attributes.transparent && !pl_attribs.no_redirection_bitmap {
-    // Empty region for the blur effect, so the window is fully transparent
-    let region = CreateRectRgn(0, 0, -1, -1);
-
-    let bb = DWM_BLURBEHIND {
-      dwFlags: DWM_BB_ENABLE | DWM_BB_BLURREGION,
-      fEnable: true.into(),
-      hRgnBlur: region,
-      fTransitionOnMaximized: false.into(),
-    };
-
-    let _ = DwmEnableBlurBehindWindow(real_window.0, &bb);
-    DeleteObject(region);
+    set_transparent_background(real_window.0);
   }
 
   // If the system theme is dark, we need to set the window theme now
@@ -901,6 +1275,29 @@ unsafe fn init<T: 'static>(
 
   win.set_skip_taskbar(pl_attribs.skip_taskbar);
 
+  // The menu bar takes its height from the client area, so it's added before the window is sized
+  if let Some(window_menu) = attributes.window_menu {
+    let window_id = RootWindowId(win.id());
+    let menu_handler = menu::MenuHandler::new(event_loop, MenuType::MenuBar, Some(window_id));
+
+    // child windows can't have a menu bar, only handle the menu commands and accelerators
+    if window_flags.contains(WindowFlags::CHILD) {
+      menu::attach(window_menu, win.hwnd(), menu_handler)?;
+    } else {
+      win.menu = Some(HMenuWrapper(menu::initialize(
+        window_menu,
+        win.hwnd(),
+        menu_handler,
+      )?));
+    }
+  }
+
+  // Moving the window first lets the frame and the size be computed with the DPI of the monitor
+  // it ends up on, and doesn't show it at the default position before.
+  if let Some(position) = attributes.position {
+    win.set_outer_position(position);
+  }
+
   let dimensions = attributes
     .inner_size
     .unwrap_or_else(|| PhysicalSize::new(800, 600).into());
@@ -917,31 +1314,25 @@ unsafe fn init<T: 'static>(
     force_window_active(win.window.0);
   }
 
-  if let Some(position) = attributes.position {
-    win.set_outer_position(position);
-  }
-
-  if let Some(window_menu) = attributes.window_menu {
-    let event_loop_runner = event_loop.runner_shared.clone();
-    let window_id = RootWindowId(win.id());
-    let menu_handler = menu::MenuHandler::new(
-      Box::new(move |event| {
-        if let Ok(e) = event.map_nonuser_event() {
-          event_loop_runner.send_event(e)
-        }
-      }),
-      MenuType::MenuBar,
-      Some(window_id),
-    );
+  Ok(win)
+}
 
-    win.menu = Some(HMenuWrapper(menu::initialize(
-      window_menu,
-      win.hwnd(),
-      menu_handler,
-    )));
-  }
+/// Lets the DWM blend the client area with what is behind it, using the alpha of its pixels.
+///
+/// Unlike a layered window, the window keeps receiving the mouse input over transparent pixels.
+pub(crate) unsafe fn set_transparent_background(hwnd: HWND) {
+  // Empty region for the blur effect, so the window is fully transparent
+  let region = CreateRectRgn(0, 0, -1, -1);
+
+  let bb = DWM_BLURBEHIND {
+    dwFlags: DWM_BB_ENABLE | DWM_BB_BLURREGION,
+    fEnable: true.into(),
+    hRgnBlur: region,
+    fTransitionOnMaximized: false.into(),
+  };
 
-  Ok(win)
+  let _ = DwmEnableBlurBehindWindow(hwnd, &bb);
+  DeleteObject(region);
 }
 
 unsafe fn register_window_class(
@@ -1045,6 +1436,7 @@ thread_local! {
     };
 
     static TASKBAR_LIST: RefCell<Option<ITaskbarList2>> = RefCell::new(None);
+    static TASKBAR_LIST3: RefCell<Option<ITaskbarList3>> = RefCell::new(None);
 }
 
 pub fn com_initialized() {
@@ -1060,6 +1452,28 @@ pub fn com_initialized() {
 // heuristics to determine how the window should be treated, which means
 // that it could still consider the window as fullscreen. :(
 unsafe fn taskbar_mark_fullscreen(handle: HWND, fullscreen: bool) {
+  with_taskbar_list(|task_bar_list| {
+    let _ = task_bar_list.MarkFullscreenWindow(handle, fullscreen);
+  })
+}
+
+unsafe fn taskbar_set_skip(handle: HWND, skip: bool) {
+  with_taskbar_list(|task_bar_list| {
+    let result = if skip {
+      task_bar_list.DeleteTab(handle)
+    } else {
+      task_bar_list.AddTab(handle)
+    };
+    if let Err(error) = result {
+      warn!(
+        "Failed to update the taskbar button of the window: {}",
+        error
+      );
+    }
+  })
+}
+
+unsafe fn with_taskbar_list(f: impl FnOnce(&ITaskbarList2)) {
   com_initialized();
 
   TASKBAR_LIST.with(|task_bar_list_ptr| {
@@ -1081,16 +1495,126 @@ unsafe fn taskbar_mark_fullscreen(handle: HWND, fullscreen: bool) {
       *task_bar_list_ptr.borrow_mut() = task_bar_list.clone();
     }
 
-    let _ = task_bar_list
-      .unwrap()
-      .MarkFullscreenWindow(handle, fullscreen);
+    f(task_bar_list.as_ref().unwrap());
   })
 }
 
+unsafe fn with_taskbar_list3(f: impl FnOnce(&ITaskbarList3)) {
+  com_initialized();
+
+  TASKBAR_LIST3.with(|task_bar_list_ptr| {
+    let mut task_bar_list = task_bar_list_ptr.borrow().clone();
+
+    if task_bar_list.is_none() {
+      let result: windows::core::Result<ITaskbarList3> =
+        CoCreateInstance(&TaskbarList, None, CLSCTX_ALL);
+      if let Ok(created) = result {
+        if let Ok(()) = created.HrInit() {
+          task_bar_list = Some(created);
+        }
+      }
+
+      if task_bar_list.is_none() {
+        return;
+      }
+
+      *task_bar_list_ptr.borrow_mut() = task_bar_list.clone();
+    }
+
+    f(task_bar_list.as_ref().unwrap());
+  })
+}
+
+unsafe fn taskbar_set_progress(handle: HWND, state: ProgressState, progress: Option<f64>) {
+  with_taskbar_list3(|task_bar_list| {
+    let flags = match state {
+      ProgressState::None => TBPF_NOPROGRESS,
+      ProgressState::Normal => TBPF_NORMAL,
+      ProgressState::Paused => TBPF_PAUSED,
+      ProgressState::Error => TBPF_ERROR,
+      ProgressState::Indeterminate => TBPF_INDETERMINATE,
+    };
+    let _ = task_bar_list.SetProgressState(handle, flags);
+    // setting a value would show the progress bar again
+    if let (Some(progress), ProgressState::Normal | ProgressState::Paused | ProgressState::Error) =
+      (progress, state)
+    {
+      let completed = (progress.clamp(0.0, 1.0) * 100.0).round() as u64;
+      let _ = task_bar_list.SetProgressValue(handle, completed, 100);
+    }
+  })
+}
+
+unsafe fn taskbar_set_overlay_icon(handle: HWND, icon: Option<&Icon>, description: &str) {
+  with_taskbar_list3(|task_bar_list| {
+    // the taskbar keeps its own copy of the icon, a null icon removes the overlay
+    let hicon = icon
+      .map(|icon| icon.inner.as_raw_handle())
+      .unwrap_or_default();
+    let _ = task_bar_list.SetOverlayIcon(handle, hicon, description);
+  })
+}
+
+unsafe fn set_window_backdrop(handle: HWND, material: BackdropMaterial) {
+  // Neither attribute is part of the metadata we build against. `DWMWA_MICA_EFFECT` is the
+  // undocumented predecessor of `DWMWA_SYSTEMBACKDROP_TYPE` on the first Windows 11 builds.
+  const DWMWA_SYSTEMBACKDROP_TYPE: DWMWINDOWATTRIBUTE = 38;
+  const DWMWA_MICA_EFFECT: DWMWINDOWATTRIBUTE = 1029;
+
+  let build = WIN10_BUILD_VERSION.unwrap_or(0);
+  let applied = if build >= 22621 {
+    let backdrop_type: i32 = match material {
+      BackdropMaterial::None => 1,
+      BackdropMaterial::Mica => 2,
+      BackdropMaterial::Acrylic => 3,
+      BackdropMaterial::Tabbed => 4,
+    };
+    DwmSetWindowAttribute(
+      handle,
+      DWMWA_SYSTEMBACKDROP_TYPE,
+      &backdrop_type as *const _ as _,
+      std::mem::size_of_val(&backdrop_type) as _,
+    )
+    .is_ok()
+  } else if build >= 22000 && matches!(material, BackdropMaterial::None | BackdropMaterial::Mica) {
+    let enabled: win32f::BOOL = (material == BackdropMaterial::Mica).into();
+    DwmSetWindowAttribute(
+      handle,
+      DWMWA_MICA_EFFECT,
+      &enabled as *const _ as _,
+      std::mem::size_of_val(&enabled) as _,
+    )
+    .is_ok()
+  } else {
+    material == BackdropMaterial::None
+  };
+
+  if !applied {
+    warn!(
+      "The {:?} backdrop isn't supported on this version of Windows",
+      material
+    );
+    return;
+  }
+
+  // The backdrop only shows through the parts of the client area drawn transparent, once the
+  // frame covers the whole window.
+  let inset = match material {
+    BackdropMaterial::None => 0,
+    _ => -1,
+  };
+  let margins = MARGINS {
+    cxLeftWidth: inset,
+    cxRightWidth: inset,
+    cyTopHeight: inset,
+    cyBottomHeight: inset,
+  };
+  let _ = DwmExtendFrameIntoClientArea(handle, &margins);
+}
+
 unsafe fn force_window_active(handle: HWND) {
   // In some situation, calling SetForegroundWindow could not bring up the window,
   // This is a little hack which can "steal" the foreground window permission
-  // We only call this function in the window creation, so it should be fine.
   // See : https://stackoverflow.com/questions/10740346/setforegroundwindow-only-working-while-visual-studio-is-open
   let alt_sc = MapVirtualKeyW(u32::from(VK_MENU), MAPVK_VK_TO_VSC);
 
@@ -1112,7 +1636,25 @@ unsafe fn force_window_active(handle: HWND) {
     mem::size_of::<INPUT>() as _,
   );
 
+  if SetForegroundWindow(handle).as_bool() {
+    return;
+  }
+
+  // The foreground lock still applies, e.g. while the user is typing in another application.
+  // Sharing the input state with the thread of the foreground window lifts it, unless it has been
+  // locked with `LockSetForegroundWindow`. Windows flashes the taskbar button otherwise.
+  let foreground_thread_id = GetWindowThreadProcessId(GetForegroundWindow(), ptr::null_mut());
+  let current_thread_id = GetCurrentThreadId();
+  let attached = foreground_thread_id != 0
+    && foreground_thread_id != current_thread_id
+    && AttachThreadInput(current_thread_id, foreground_thread_id, true).as_bool();
+
+  BringWindowToTop(handle);
   SetForegroundWindow(handle);
+
+  if attached {
+    AttachThreadInput(current_thread_id, foreground_thread_id, false);
+  }
 }
 
 pub fn hit_test(hwnd: HWND, cx: i32, cy: i32) -> LRESULT {
@@ -23,9 +23,13 @@ pub struct WindowState {
   /// Used by `WM_GETMINMAXINFO`.
   pub min_size: Option<Size>,
   pub max_size: Option<Size>,
+  /// Width and height ratio of the inner size kept by `WM_SIZING`.
+  pub aspect_ratio: Option<(u32, u32)>,
 
   pub window_icon: Option<Icon>,
   pub taskbar_icon: Option<Icon>,
+  /// The taskbar adds a button again whenever the window is shown, it is removed every time.
+  pub skip_taskbar: bool,
 
   pub saved_window: Option<SavedWindow>,
   pub scale_factor: f64,
@@ -60,6 +64,7 @@ bitflags! {
         const GRABBED   = 1 << 0;
         const HIDDEN    = 1 << 1;
         const IN_WINDOW = 1 << 2;
+        const LOCKED    = 1 << 3;
     }
 }
 bitflags! {
@@ -74,6 +79,9 @@ bitflags! {
         const CHILD          = 1 << 7;
         const MAXIMIZED      = 1 << 8;
         const POPUP          = 1 << 14;
+        const LAYERED        = 1 << 15;
+        const MINIMIZABLE    = 1 << 16;
+        const MAXIMIZABLE    = 1 << 17;
 
         /// Marker flag for fullscreen. Should always match `WindowState::fullscreen`, but is
         /// included here to make masking easier.
@@ -113,9 +121,11 @@ impl WindowState {
 
       min_size: attributes.min_inner_size,
       max_size: attributes.max_inner_size,
+      aspect_ratio: None,
 
       window_icon: attributes.window_icon.clone(),
       taskbar_icon,
+      skip_taskbar: false,
 
       saved_window: None,
       scale_factor,
@@ -189,11 +199,17 @@ impl WindowFlags {
 
   pub fn to_window_styles(self) -> (WINDOW_STYLE, WINDOW_EX_STYLE) {
     let (mut style, mut style_ex) = (0, 0);
-    style |= WS_CLIPSIBLINGS | WS_CLIPCHILDREN | WS_SYSMENU | WS_CAPTION | WS_MINIMIZEBOX;
+    style |= WS_CLIPSIBLINGS | WS_CLIPCHILDREN | WS_SYSMENU | WS_CAPTION;
     style_ex |= WS_EX_ACCEPTFILES;
 
     if self.contains(WindowFlags::RESIZABLE) {
-      style |= WS_THICKFRAME | WS_MAXIMIZEBOX;
+      style |= WS_THICKFRAME;
+    }
+    if self.contains(WindowFlags::MINIMIZABLE) {
+      style |= WS_MINIMIZEBOX;
+    }
+    if self.contains(WindowFlags::RESIZABLE | WindowFlags::MAXIMIZABLE) {
+      style |= WS_MAXIMIZEBOX;
     }
     if self.contains(WindowFlags::DECORATIONS) {
       style |= WS_BORDER;
@@ -211,6 +227,9 @@ impl WindowFlags {
     if self.contains(WindowFlags::NO_BACK_BUFFER) {
       style_ex |= WS_EX_NOREDIRECTIONBITMAP;
     }
+    if self.contains(WindowFlags::LAYERED) {
+      style_ex |= WS_EX_LAYERED;
+    }
     if self.contains(WindowFlags::CHILD) {
       style |= WS_CHILD; // This is incompatible with WS_POPUP if that gets added eventually.
     }
@@ -330,9 +349,20 @@ impl CursorFlags {
     let client_rect = util::get_client_rect(window)?;
 
     if util::is_focused(window) {
-      let cursor_clip = match self.contains(CursorFlags::GRABBED) {
-        true => Some(client_rect),
-        false => None,
+      let cursor_clip = if self.contains(CursorFlags::LOCKED) {
+        // a clip of a single pixel keeps the cursor there
+        let x = (client_rect.left + client_rect.right) / 2;
+        let y = (client_rect.top + client_rect.bottom) / 2;
+        Some(RECT {
+          left: x,
+          top: y,
+          right: x + 1,
+          bottom: y + 1,
+        })
+      } else if self.contains(CursorFlags::GRABBED) {
+        Some(client_rect)
+      } else {
+        None
       };
 
       let rect_to_tuple = |rect: RECT| (rect.left, rect.top, rect.right, rect.bottom);
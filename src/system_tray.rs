@@ -109,4 +109,14 @@ impl SystemTray {
   pub fn set_menu(&mut self, tray_menu: &ContextMenu) {
     self.0.set_menu(&tray_menu.0.menu_platform)
   }
+
+  /// Set the tooltip shown when hovering the tray icon.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Truncated to 127 characters.
+  /// - **Linux:** Unsupported
+  pub fn set_tooltip(&mut self, tooltip: &str) {
+    self.0.set_tooltip(tooltip)
+  }
 }
@@ -152,6 +152,16 @@ pub struct WindowAttributes {
   /// The default is `true`.
   pub resizable: bool,
 
+  /// Whether the window can be minimized.
+  ///
+  /// The default is `true`.
+  pub minimizable: bool,
+
+  /// Whether the window can be maximized.
+  ///
+  /// The default is `true`.
+  pub maximizable: bool,
+
   /// Whether the window should be set as fullscreen upon creation.
   ///
   /// The default is `None`.
@@ -208,6 +218,8 @@ impl Default for WindowAttributes {
       max_inner_size: None,
       position: None,
       resizable: true,
+      minimizable: true,
+      maximizable: true,
       title: "tao window".to_owned(),
       maximized: false,
       fullscreen: None,
@@ -283,6 +295,28 @@ impl WindowBuilder {
     self
   }
 
+  /// Sets whether the window can be minimized.
+  ///
+  /// See [`Window::set_minimizable`] for details.
+  ///
+  /// [`Window::set_minimizable`]: crate::window::Window::set_minimizable
+  #[inline]
+  pub fn with_minimizable(mut self, minimizable: bool) -> Self {
+    self.window.minimizable = minimizable;
+    self
+  }
+
+  /// Sets whether the window can be maximized.
+  ///
+  /// See [`Window::set_maximizable`] for details.
+  ///
+  /// [`Window::set_maximizable`]: crate::window::Window::set_maximizable
+  #[inline]
+  pub fn with_maximizable(mut self, maximizable: bool) -> Self {
+    self.window.maximizable = maximizable;
+    self
+  }
+
   /// Requests a specific title for the window.
   ///
   /// See [`Window::set_title`] for details.
@@ -298,6 +332,12 @@ impl WindowBuilder {
   ///
   /// See [`Window::set_menu`] for details.
   ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** A menu bar can only belong to one window. When `menu` is already used by
+  ///   another window, this window gets a copy of it that stays in sync, see
+  ///   [`MenuExtWindows::mirror`](crate::platform::windows::MenuExtWindows::mirror).
+  ///
   /// [`Window::set_menu`]: crate::window::Window::set_menu
   #[inline]
   pub fn with_menu(mut self, menu: MenuBar) -> Self {
@@ -339,6 +379,16 @@ impl WindowBuilder {
   }
 
   /// Sets whether the background of the window should be transparent.
+  ///
+  /// The window is then blended with what is behind it using the alpha of each pixel, which the
+  /// rendering backend must write premultiplied. The transparent parts still receive the mouse
+  /// input.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Uses the DWM, combine with [`WindowBuilder::with_decorations`] for
+  ///   non-rectangular windows. Not applied with `WindowBuilderExtWindows::with_no_redirection_bitmap`,
+  ///   which leaves the drawing to DirectComposition.
   #[inline]
   pub fn with_transparent(mut self, transparent: bool) -> Self {
     self.window.transparent = transparent;
@@ -490,6 +540,8 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** The window includes the invisible resize borders around the frame of
+  ///   Windows 10 and later.
   /// - **iOS:** Can only be called on the main thread. Returns the top left coordinates of the
   ///   window in the screen space coordinate system.
   /// - **Android:** Always returns [`NotSupportedError`].
@@ -505,6 +557,8 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** A logical position is converted with the scale factor of the monitor the
+  ///   window is on before it's moved.
   /// - **iOS:** Can only be called on the main thread. Sets the top left coordinates of the
   ///   window in the screen space coordinate system.
   /// - **Android:** Unsupported.
@@ -548,6 +602,8 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** Like [`Window::outer_position`], this includes the invisible resize borders.
+  ///   The size of the frame depends on the DPI of the monitor the window is on.
   /// - **iOS:** Can only be called on the main thread. Returns the `PhysicalSize` of the window in
   ///   screen space coordinates.
   #[inline]
@@ -617,8 +673,15 @@ impl Window {
 
   /// Bring the window to front and focus.
   ///
+  /// A [`WindowEvent::Focused`](crate::event::WindowEvent::Focused) event is emitted once the
+  /// window has the focus.
+  ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** Does nothing if the window is hidden or minimized. Windows doesn't let an
+  ///   application take the focus away from another one in every case, e.g. when the foreground
+  ///   application has called `LockSetForegroundWindow`. The taskbar button of the window
+  ///   flashes instead.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_focus(&self) {
@@ -644,6 +707,39 @@ impl Window {
     self.window.set_resizable(resizable)
   }
 
+  /// Sets whether the window can be minimized by the user, with the minimize button of the title
+  /// bar or the window menu.
+  ///
+  /// [`Window::set_minimized`] still minimizes the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The minimize button is grayed out, unless the maximize button is also
+  ///   disabled, in which case both are removed.
+  /// - **macOS:** The minimize button is disabled.
+  /// - **Linux / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_minimizable(&self, minimizable: bool) {
+    self.window.set_minimizable(minimizable)
+  }
+
+  /// Sets whether the window can be maximized by the user, with the maximize button of the title
+  /// bar, the window menu or a double click on the title bar.
+  ///
+  /// [`Window::set_maximized`] still maximizes the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** A window which isn't resizable can't be maximized either. The maximize
+  ///   button is grayed out, unless the minimize button is also disabled, in which case both are
+  ///   removed.
+  /// - **macOS:** The zoom button is disabled.
+  /// - **Linux / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_maximizable(&self, maximizable: bool) {
+    self.window.set_maximizable(maximizable)
+  }
+
   /// Sets the window to minimized or back
   ///
   /// ## Platform-specific
@@ -720,7 +816,8 @@ impl Window {
   ///
   ///   The dock and the menu bar are always disabled in fullscreen mode.
   /// - **iOS:** Can only be called on the main thread.
-  /// - **Windows:** Screen saver is disabled in fullscreen mode.
+  /// - **Windows:** Screen saver is disabled in fullscreen mode. The window bounds before entering
+  ///   fullscreen are restored when leaving it, also after switching between modes or monitors.
   /// - **Linux:** The window will only fullscreen to current monitor no matter which enum variant.
   /// - **Android:** Unsupported.
   #[inline]
@@ -743,6 +840,9 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** The inner size and position are kept, the frame is added or removed around
+  ///   them. An undecorated window can still be moved and resized with [`Window::drag_window`] and
+  ///   from its borders.
   /// - **iOS / Android:** Unsupported.
   ///
   /// [`setPrefersStatusBarHidden`]: https://developer.apple.com/documentation/uikit/uiviewcontroller/1621440-prefersstatusbarhidden?language=objc
@@ -755,12 +855,32 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** Neither moves, resizes nor activates the window. Turning it off puts the window
+  ///   back in the normal z-order, right below the topmost windows. A window in exclusive
+  ///   fullscreen stays topmost until it leaves fullscreen.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_always_on_top(&self, always_on_top: bool) {
     self.window.set_always_on_top(always_on_top)
   }
 
+  /// Sets the opacity of the whole window, from `0.0` (fully transparent) to `1.0` (fully opaque).
+  /// Values outside that range are clamped.
+  ///
+  /// This fades the window and its decorations uniformly. It is unrelated to per-pixel
+  /// transparency, which is requested with [`WindowBuilder::with_transparent`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The window is made layered while it is translucent. Going back to `1.0` removes
+  ///   the layered style again.
+  /// - **Linux:** Requires a compositing window manager.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_opacity(&self, opacity: f64) {
+    self.window.set_opacity(opacity)
+  }
+
   /// Sets the window icon. On Windows and Linux, this is typically the small icon in the top-left
   /// corner of the title bar.
   ///
@@ -768,7 +888,9 @@ impl Window {
   ///
   /// - **iOS / Android / macOS:** Unsupported.
   ///
-  /// On Windows, this sets `ICON_SMALL`. The base size for a window icon is 16x16, but it's
+  /// On Windows, this sets `ICON_SMALL`, and `ICON_BIG` unless a taskbar icon was set with
+  /// [`WindowExtWindows::set_taskbar_icon`](crate::platform::windows::WindowExtWindows::set_taskbar_icon).
+  /// `None` restores the default icon. The base size for a window icon is 16x16, but it's
   /// recommended to account for screen scaling and pick a multiple of that, i.e. 32x32.
   #[inline]
   pub fn set_window_icon(&self, window_icon: Option<Icon>) {
@@ -785,6 +907,19 @@ impl Window {
     self.window.set_ime_position(position.into())
   }
 
+  /// Sets whether the input method can be used in the window, e.g. disabled while playing a game
+  /// and enabled again for a chat box. It is allowed by default.
+  ///
+  /// While it is allowed, compositions are reported by [`WindowEvent::Ime`](crate::event::WindowEvent::Ime).
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_ime_allowed(&self, allowed: bool) {
+    self.window.set_ime_allowed(allowed)
+  }
+
   /// Requests user attention to the window, this has no effect if the application
   /// is already focused. How requesting for user attention manifests is platform dependent,
   /// see `UserAttentionType` for details.
@@ -837,8 +972,12 @@ impl Window {
 impl Window {
   /// Modifies the cursor icon of the window.
   ///
+  /// The icon is shown whenever the cursor is over the client area of the window, and stays
+  /// the same until changed again.
+  ///
   /// ## Platform-specific
   ///
+  /// - **Windows:** Icons without a system cursor, e.g. `ZoomIn`, show the arrow.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_cursor_icon(&self, cursor: CursorIcon) {
@@ -855,18 +994,20 @@ impl Window {
     self.window.set_cursor_position(position.into())
   }
 
-  /// Grabs the cursor, preventing it from leaving the window.
+  /// Grabs the cursor with `mode`, or releases it with [`CursorGrabMode::None`].
   ///
+  /// The grab is released while the window is unfocused and applied again when it regains focus.
   /// There's no guarantee that the cursor will be hidden. You should
   /// hide it by yourself if you want so.
   ///
   /// ## Platform-specific
   ///
-  /// - **macOS:** This locks the cursor in a fixed location, which looks visually awkward.
-  /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+  /// - **macOS:** `Confined` isn't supported and returns an [`ExternalError::NotSupported`].
+  /// - **Windows:** `Locked` keeps the cursor at the center of the window.
+  /// - **Linux / iOS / Android:** Always returns an [`ExternalError::NotSupported`].
   #[inline]
-  pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
-    self.window.set_cursor_grab(grab)
+  pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
+    self.window.set_cursor_grab(mode)
   }
 
   /// Modifies the cursor's visibility.
@@ -892,6 +1033,7 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **macOS:** May prevent the button release event to be triggered.
+  /// - **Windows:** Returns an [`ExternalError::NotSupported`] if the left mouse button isn't pressed.
   /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
   #[inline]
   pub fn drag_window(&self) -> Result<(), ExternalError> {
@@ -903,11 +1045,18 @@ impl Window {
 impl Window {
   /// Returns the monitor on which the window currently resides.
   ///
+  /// When the window spans several monitors, this is the one with the largest part of the
+  /// window. The handle compares equal to the same monitor returned by
+  /// [`Window::available_monitors`].
+  ///
   /// Returns `None` if current monitor can't be detected.
   ///
   /// ## Platform-specific
   ///
-  /// **iOS:** Can only be called on the main thread.
+  /// - **Windows:** The nearest monitor is returned when the window is outside of all of them.
+  /// - **macOS:** Returns `None` when the window is entirely offscreen.
+  /// - **Linux:** Returns `None` before the window is realized.
+  /// - **iOS:** Can only be called on the main thread.
   #[inline]
   pub fn current_monitor(&self) -> Option<MonitorHandle> {
     self.window.current_monitor()
@@ -1023,12 +1172,35 @@ impl Default for CursorIcon {
   }
 }
 
+/// How the cursor is grabbed by a window, see [`Window::set_cursor_grab`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CursorGrabMode {
+  /// The cursor moves freely.
+  None,
+  /// The cursor can't leave the client area of the window.
+  Confined,
+  /// The cursor doesn't move, e.g. for first-person camera controls. Its movements are still
+  /// reported by [`DeviceEvent::MouseMotion`](crate::event::DeviceEvent::MouseMotion).
+  Locked,
+}
+
+impl Default for CursorGrabMode {
+  fn default() -> Self {
+    CursorGrabMode::None
+  }
+}
+
 /// Fullscreen modes.
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq)]
 pub enum Fullscreen {
+  /// Changes the display mode of the monitor of the video mode, see [`MonitorHandle::video_modes`].
   Exclusive(VideoMode),
 
+  /// Covers the whole monitor with the window, without changing its display mode.
+  ///
   /// Providing `None` to `Borderless` will fullscreen on the current monitor.
   Borderless(Option<MonitorHandle>),
 }
@@ -1049,7 +1221,8 @@ pub enum UserAttentionType {
   Critical,
   /// ## Platform-specific
   /// - **macOS:** Bounces the dock icon once.
-  /// - **Windows:** Flashes the taskbar button until the application is in focus.
+  /// - **Windows:** Flashes the taskbar button a few times, then keeps it highlighted until the
+  ///   application is in focus.
   Informational,
 }
 